@@ -1,12 +1,11 @@
-use crossbeam::channel::IntoIter;
 use heart::{self, *};
 
-fn main() {
+fn main() -> Result<(), heart::Error> {
     heart::build()
         .with_load(State::new)
         .with_update(State::update)
         .with_draw(State::draw)
-        .run();
+        .run()
 }
 
 struct State {
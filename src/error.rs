@@ -0,0 +1,80 @@
+use std::fmt;
+
+/// An error that prevented [heart][crate] from running.
+///
+/// Returned by [run][crate::HeartBuilder::run], and by [draw][crate::testing::Harness::draw]
+/// when the renderer of a test cannot be created.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The event loop could not be created or failed while running.
+    EventLoop(winit::error::EventLoopError),
+
+    /// The window could not be created.
+    Window(winit::error::OsError),
+
+    /// A drawing surface could not be created for the window.
+    Surface(wgpu::CreateSurfaceError),
+
+    /// No graphics adapter is able to draw to the window.
+    NoAdapter,
+
+    /// The window's surface does not support a texture format that [heart][crate] can draw with.
+    UnsupportedSurfaceFormat,
+
+    /// The graphics device could not be created from the adapter.
+    Device(wgpu::RequestDeviceError),
+
+    /// The graphics device ran out of memory while drawing.
+    OutOfMemory,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EventLoop(error) => write!(f, "event loop error: {error}"),
+            Self::Window(error) => write!(f, "failed to create window: {error}"),
+            Self::Surface(error) => write!(f, "failed to create surface: {error}"),
+            Self::NoAdapter => write!(f, "no compatible graphics adapter found"),
+            Self::UnsupportedSurfaceFormat => write!(f, "surface format not supported"),
+            Self::Device(error) => write!(f, "failed to create graphics device: {error}"),
+            Self::OutOfMemory => write!(f, "graphics device out of memory"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::EventLoop(error) => Some(error),
+            Self::Window(error) => Some(error),
+            Self::Surface(error) => Some(error),
+            Self::Device(error) => Some(error),
+            Self::NoAdapter | Self::UnsupportedSurfaceFormat | Self::OutOfMemory => None,
+        }
+    }
+}
+
+impl From<winit::error::EventLoopError> for Error {
+    fn from(value: winit::error::EventLoopError) -> Self {
+        Self::EventLoop(value)
+    }
+}
+
+impl From<winit::error::OsError> for Error {
+    fn from(value: winit::error::OsError) -> Self {
+        Self::Window(value)
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for Error {
+    fn from(value: wgpu::CreateSurfaceError) -> Self {
+        Self::Surface(value)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for Error {
+    fn from(value: wgpu::RequestDeviceError) -> Self {
+        Self::Device(value)
+    }
+}
@@ -0,0 +1,49 @@
+//! Communication between callbacks.
+//!
+//! Any `'static` type that is [Send] can be used as an event. Events are queued by [send] and
+//! delivered once per tick, before the [update][crate::HeartBuilder::with_update] functions, to every
+//! [event][crate::HeartBuilder::with_event] function that takes the same type of event.
+//! Events are delivered in the order they were sent. Events sent while events are being
+//! delivered are delivered on the next tick.
+//!
+//! Unlike most of [heart][crate], [send] can be called from other threads. This lets work that
+//! runs in the background, such as loading a level or waiting for the network, report back to
+//! the game, which is why events must be [Send].
+//!
+//! ```no_run
+//! struct PlayerDied {
+//!     score: u32,
+//! }
+//!
+//! struct Audio;
+//!
+//! heart::build()
+//!     .with_load(|| Audio)
+//!     .with_event(|_: &mut Audio, _: &PlayerDied| println!("play a sad sound"))
+//!     .with_event(|event: &PlayerDied| println!("game over with {} points", event.score))
+//!     .with_update(|| {
+//!         if heart::time::get_ticks() == 60 {
+//!             heart::events::send(PlayerDied { score: 100 });
+//!         }
+//!     })
+//!     .run()
+//!     .unwrap();
+//! ```
+//!
+//! See also:  
+//! [with event][crate::HeartBuilder::with_event]  
+
+use std::any::Any;
+
+pub(crate) mod state;
+
+/// Queues an event for delivery on the next tick.
+///
+/// Events sent from other threads are delivered on the next tick that starts after they were sent.
+/// Events that no function takes are dropped.
+pub fn send<E>(event: E)
+where
+    E: Any + Send,
+{
+    state::send(event);
+}
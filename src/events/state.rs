@@ -0,0 +1,24 @@
+use std::{
+    any::{Any, TypeId},
+    sync::Mutex,
+};
+
+static QUEUE: Mutex<Vec<(TypeId, Box<dyn Any + Send>)>> = Mutex::new(Vec::new());
+
+pub(crate) fn init() {
+    QUEUE.lock().unwrap().clear();
+}
+
+pub(crate) fn send<E>(event: E)
+where
+    E: Any + Send,
+{
+    QUEUE
+        .lock()
+        .unwrap()
+        .push((TypeId::of::<E>(), Box::new(event)));
+}
+
+pub(crate) fn take() -> Vec<(TypeId, Box<dyn Any + Send>)> {
+    std::mem::take(&mut *QUEUE.lock().unwrap())
+}
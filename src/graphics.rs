@@ -6,14 +6,70 @@
 //!
 //! Functions that can be called outside of [draw][crate::HeartBuilder::with_draw]:
 //!
-//! [create_sprite]
+//! [create_sprite]  
+//! [screenshot]  
+//! [request_redraw]  
+//! [adapter_info]
 
+pub(crate) mod canvas;
 pub(crate) mod context;
 pub(crate) mod rectangle;
 pub(crate) mod renderer;
 pub(crate) mod sprite;
 pub(crate) mod transform;
 
+/// Represents the way frames are presented to the screen.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PresentMode {
+    /// Waits for the display's vertical blank. Limits the frame rate to the refresh rate.
+    Vsync,
+
+    /// Presents frames as soon as possible. May cause tearing.
+    Immediate,
+
+    /// Waits for the display's vertical blank, but replaces queued frames instead of blocking.
+    ///
+    /// Falls back to [Vsync][PresentMode::Vsync] if not supported.
+    Mailbox,
+}
+
+/// Represents the way a [virtual resolution][crate::HeartBuilder::with_virtual_resolution]
+/// is scaled to the window.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ScalingMode {
+    /// Scales the canvas as much as possible while keeping it fully visible.
+    /// The remaining area is filled with black bars.
+    Fit,
+
+    /// Scales the canvas to cover the whole window. Parts of the canvas may be cut off.
+    Fill,
+
+    /// Stretches the canvas to the size of the window, ignoring its aspect ratio.
+    Stretch,
+
+    /// Like [Fit][ScalingMode::Fit], but only scales by whole numbers and without smoothing,
+    /// so every pixel of the canvas stays sharp and equally sized.
+    PixelPerfect,
+}
+
+/// Represents a change to the surface that the window is drawn on.
+///
+/// See [surface event][crate::HeartBuilder::with_surface_event].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceEvent {
+    /// The surface was lost and had to be recreated. The frame was skipped.
+    Lost,
+
+    /// The surface no longer matched the window and had to be recreated. The frame was skipped.
+    Outdated,
+
+    /// The window was minimized. Nothing is drawn until it is restored.
+    Minimized,
+
+    /// The window was restored after being minimized.
+    Restored,
+}
+
 /// Trait for drawable types.
 pub trait Draw {
     /// Draw `self`.
@@ -36,6 +92,31 @@ pub fn create_sprite(image: crate::image::Image) -> Sprite {
     ))
 }
 
+/// Get information about the graphics adapter that was chosen by [heart][crate].
+///
+/// See [with_power_preference][crate::HeartBuilder::with_power_preference] and
+/// [with_backends][crate::HeartBuilder::with_backends] for influencing the choice.
+pub fn adapter_info() -> wgpu::AdapterInfo {
+    context::get().renderer.adapter.get_info()
+}
+
+/// Requests a screenshot of the current frame of the [current][crate::window::current] window.
+///
+/// Once the frame has been drawn, it is read back and passed as an
+/// [Image][crate::image::Image] to the [screenshot][crate::HeartBuilder::with_screenshot] functions.
+/// If the frame cannot be read back from the graphics device, the screenshot is skipped.
+pub fn screenshot() {
+    context::get().screenshot_requested = Some(crate::window::current());
+}
+
+/// Requests the windows to be drawn again.
+///
+/// This is only needed when [redraw on demand][crate::HeartBuilder::with_redraw_on_demand] is enabled.
+/// Calling it from a [draw][crate::HeartBuilder::with_draw] function keeps drawing every frame, for example while an animation plays.
+pub fn request_redraw() {
+    context::get().redraw_requested = true;
+}
+
 /// Resets the drawing settings.
 pub fn reset() {
     context::get().draw_state = context::DrawState::default();
@@ -64,15 +145,7 @@ pub fn rectangle(x: f32, y: f32, width: f32, height: f32) {
         color: context.draw_state.color,
         transform: context.draw_state.transform,
     };
-    match context.render_list.commands.last_mut() {
-        Some(renderer::RenderCommand::RectangleBatch(batch)) => batch.add(&draw_info),
-        _ => context
-            .render_list
-            .commands
-            .push(renderer::RenderCommand::RectangleBatch(
-                rectangle::RectangleBatch::new(&draw_info),
-            )),
-    }
+    context.add_rectangle(&draw_info);
 }
 
 /// Draws a [drawable][Draw].
@@ -132,10 +205,7 @@ impl Draw for Sprite {
         };
         if let Some(batch) = match context.render_list.commands.last_mut() {
             Some(renderer::RenderCommand::SpriteBatch(batch)) => {
-                match batch.try_add(&draw_info, &mut context.renderer) {
-                    Err(batch) => Some(batch),
-                    _ => None,
-                }
+                batch.try_add(&draw_info, &mut context.renderer).err()
             }
             _ => Some(sprite::SpriteBatch::new(&draw_info, &mut context.renderer)),
         } {
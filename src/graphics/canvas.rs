@@ -0,0 +1,286 @@
+use zerocopy::IntoBytes;
+
+use super::{
+    ScalingMode,
+    renderer::{Color, Uniforms},
+};
+
+#[derive(Clone, Copy)]
+pub(crate) struct VirtualResolution {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) mode: ScalingMode,
+}
+
+impl VirtualResolution {
+    pub(crate) fn rect(&self, target_width: u32, target_height: u32) -> [f32; 4] {
+        let (width, height) = (self.width as f32, self.height as f32);
+        let (target_width, target_height) = (target_width as f32, target_height as f32);
+        let (scale_x, scale_y) = match self.mode {
+            ScalingMode::Fit => {
+                let scale = f32::min(target_width / width, target_height / height);
+                (scale, scale)
+            }
+            ScalingMode::Fill => {
+                let scale = f32::max(target_width / width, target_height / height);
+                (scale, scale)
+            }
+            ScalingMode::Stretch => (target_width / width, target_height / height),
+            ScalingMode::PixelPerfect => {
+                let scale = f32::min(target_width / width, target_height / height)
+                    .floor()
+                    .max(1.0);
+                (scale, scale)
+            }
+        };
+        [
+            ((target_width - width * scale_x) / 2.0).round(),
+            ((target_height - height * scale_y) / 2.0).round(),
+            width * scale_x,
+            height * scale_y,
+        ]
+    }
+
+    pub(crate) fn map_position(
+        &self,
+        x: f32,
+        y: f32,
+        target_width: u32,
+        target_height: u32,
+    ) -> (f32, f32) {
+        let [left, top, width, height] = self.rect(target_width, target_height);
+        (
+            (x - left) * self.width as f32 / width,
+            (y - top) * self.height as f32 / height,
+        )
+    }
+}
+
+pub(crate) struct Canvas {
+    pub(crate) resolution: VirtualResolution,
+    pub(crate) texture: wgpu::Texture,
+    pub(crate) uniforms: Uniforms,
+    blit: Blit,
+}
+
+impl Canvas {
+    pub(crate) fn new(
+        resolution: VirtualResolution,
+        uniforms: Uniforms,
+        format: wgpu::TextureFormat,
+        device: &wgpu::Device,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: resolution.width,
+                height: resolution.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        let filter_mode = match resolution.mode {
+            ScalingMode::PixelPerfect => wgpu::FilterMode::Nearest,
+            _ => wgpu::FilterMode::Linear,
+        };
+        let blit = Blit::new(&texture, filter_mode, format, device);
+
+        Self {
+            resolution,
+            texture,
+            uniforms,
+            blit,
+        }
+    }
+
+    pub(crate) fn encode_blit(
+        &self,
+        target: &wgpu::Texture,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let rect = self.resolution.rect(target.width(), target.height());
+        self.blit.encode(rect, target, queue, encoder);
+    }
+}
+
+/// Draws a texture onto a rectangle of another texture, clearing the rest to black.
+pub(crate) struct Blit {
+    rect_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl Blit {
+    pub(crate) fn new(
+        source: &wgpu::Texture,
+        filter_mode: wgpu::FilterMode,
+        format: wgpu::TextureFormat,
+        device: &wgpu::Device,
+    ) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: filter_mode,
+            min_filter: filter_mode,
+            ..Default::default()
+        });
+
+        let rect_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: rect_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(
+                        &source.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let pipeline = create_pipeline(&bind_layout, format, device);
+
+        Self {
+            rect_buffer,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    pub(crate) fn encode(
+        &self,
+        [x, y, width, height]: [f32; 4],
+        target: &wgpu::Texture,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let (target_width, target_height) = (target.width() as f32, target.height() as f32);
+        let rect = [
+            x / target_width * 2.0 - 1.0,
+            1.0 - y / target_height * 2.0,
+            (x + width) / target_width * 2.0 - 1.0,
+            1.0 - (y + height) / target_height * 2.0,
+        ];
+        queue.write_buffer(&self.rect_buffer, 0, rect.as_bytes());
+
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(Color::default().into()),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..4, 0..1);
+    }
+}
+
+fn create_pipeline(
+    bind_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+    device: &wgpu::Device,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(include_str!("canvas.wgsl").into()),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[bind_layout],
+        ..Default::default()
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: None,
+            buffers: &[],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: None,
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
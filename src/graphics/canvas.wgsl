@@ -0,0 +1,22 @@
+@group(0) @binding(0) var<uniform> rect: vec4<f32>;
+@group(0) @binding(1) var canvas: texture_2d<f32>;
+@group(0) @binding(2) var canvas_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vertex(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let corner = vec2(f32(index & 1u), f32(index >> 1u));
+    return VertexOutput(
+        vec4(mix(rect.xy, rect.zw, corner), 0.0, 1.0),
+        corner,
+    );
+}
+
+@fragment
+fn fragment(input: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(canvas, canvas_sampler, input.tex_coords);
+}
@@ -1,10 +1,13 @@
 use std::{
-    ops::DerefMut,
-    sync::{Arc, Mutex, OnceLock},
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard, PoisonError},
 };
 
+use crate::{Error, image::Image, window};
+
 use super::{
-    renderer::{Color, RenderList, Renderer},
+    rectangle::{RectangleBatch, RectangleDrawInfo},
+    renderer::{Color, RenderCommand, RenderList, Renderer, Uniforms},
     transform::Transform,
 };
 
@@ -12,6 +15,8 @@ pub(crate) struct Context {
     pub(crate) renderer: Renderer,
     pub(crate) render_list: RenderList,
     pub(crate) draw_state: DrawState,
+    pub(crate) screenshot_requested: Option<&'static str>,
+    pub(crate) redraw_requested: bool,
 }
 
 impl Context {
@@ -22,8 +27,44 @@ impl Context {
         self.draw_state = DrawState::default();
     }
 
-    pub(crate) fn render(&mut self, view: wgpu::TextureView) {
-        self.renderer.render(&self.render_list, view)
+    pub(crate) fn add_rectangle(&mut self, draw_info: &RectangleDrawInfo) {
+        match self.render_list.commands.last_mut() {
+            Some(RenderCommand::RectangleBatch(batch)) => batch.add(draw_info),
+            _ => self
+                .render_list
+                .commands
+                .push(RenderCommand::RectangleBatch(RectangleBatch::new(
+                    draw_info,
+                ))),
+        }
+    }
+
+    pub(crate) fn cover(&mut self, color: Color) {
+        let (width, height) = match &self.renderer.canvas {
+            Some(canvas) if window::current() == window::MAIN => {
+                (canvas.resolution.width, canvas.resolution.height)
+            }
+            _ => window::state::get_size(),
+        };
+        self.add_rectangle(&RectangleDrawInfo {
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: height as f32,
+            color,
+            transform: Transform::identity(),
+        });
+    }
+
+    pub(crate) fn render(&mut self, target: &wgpu::Texture, uniforms: &Uniforms) -> Option<Image> {
+        let capture = self
+            .screenshot_requested
+            .take_if(|name| *name == window::current())
+            .is_some();
+        // The virtual resolution only applies to the MAIN window.
+        let canvas = window::current() == window::MAIN;
+        self.renderer
+            .render(&self.render_list, target, uniforms, canvas, capture)
     }
 }
 
@@ -41,24 +82,98 @@ impl Default for DrawState {
     }
 }
 
-static CONTEXT: OnceLock<Arc<Mutex<Context>>> = OnceLock::new();
+static CONTEXT: Mutex<Option<Context>> = Mutex::new(None);
+
+type CreateRenderer = Box<dyn Fn() -> Result<Renderer, Error> + Send>;
+
+// Creates the renderer the first time the context is needed, if it was not created up front.
+static CREATE_RENDERER: Mutex<Option<CreateRenderer>> = Mutex::new(None);
+
+// Only created by `get` while the context exists.
+struct Guard(MutexGuard<'static, Option<Context>>);
+
+impl Deref for Guard {
+    type Target = Context;
+
+    fn deref(&self) -> &Context {
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for Guard {
+    fn deref_mut(&mut self) -> &mut Context {
+        self.0.as_mut().unwrap()
+    }
+}
+
+// A panic while the context is locked must not stop later sessions from using it,
+// so poisoning is ignored.
+fn lock() -> MutexGuard<'static, Option<Context>> {
+    CONTEXT.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
 pub(crate) fn init(renderer: Renderer) {
-    let _ = CONTEXT.set(Arc::new(Mutex::new(Context {
+    *lock() = Some(Context {
         renderer,
         render_list: RenderList::default(),
         draw_state: DrawState::default(),
-    })));
+        screenshot_requested: None,
+        redraw_requested: false,
+    });
+}
+
+pub(crate) fn init_lazily(create_renderer: CreateRenderer) {
+    *CREATE_RENDERER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(create_renderer);
+}
+
+/// Creates the renderer passed to [init_lazily] unless the context already exists.
+pub(crate) fn ensure() -> Result<(), Error> {
+    let create_renderer = CREATE_RENDERER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(create_renderer) = &*create_renderer
+        && lock().is_none()
+    {
+        init(create_renderer()?);
+    }
+    Ok(())
+}
+
+pub(crate) fn deinit() {
+    CREATE_RENDERER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    let context = lock().take();
+    drop(context);
 }
 
 pub(crate) fn get() -> impl DerefMut<Target = Context> {
-    CONTEXT.get().unwrap().lock().unwrap()
+    if let Err(error) = ensure() {
+        panic!("heart::graphics: {error}");
+    }
+    let guard = lock();
+    if guard.is_none() {
+        drop(guard);
+        panic!("heart::graphics: heart is not running");
+    }
+    Guard(guard)
+}
+
+pub(crate) fn take_redraw_request() -> bool {
+    std::mem::take(&mut get().redraw_requested)
 }
 
 pub(crate) fn reset() {
     get().reset();
 }
 
-pub(crate) fn render(view: wgpu::TextureView) {
-    get().render(view);
+pub(crate) fn render(target: &wgpu::Texture, uniforms: &Uniforms) -> Option<Image> {
+    get().render(target, uniforms)
+}
+
+pub(crate) fn cover(color: Color) {
+    get().cover(color);
 }
@@ -0,0 +1,12 @@
+override encode_srgb: bool = false;
+
+fn output(color: vec4<f32>) -> vec4<f32> {
+    if encode_srgb {
+        let rgb = max(color.rgb, vec3(0.0));
+        return vec4(
+            select(1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055, rgb * 12.92, rgb <= vec3(0.0031308)),
+            color.a
+        );
+    }
+    return color;
+}
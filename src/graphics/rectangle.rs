@@ -7,11 +7,14 @@ use super::{
 
 pub(crate) fn create_pipeline(
     uniform_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
     device: &wgpu::Device,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: None,
-        source: wgpu::ShaderSource::Wgsl(include_str!("rectangle.wgsl").into()),
+        source: wgpu::ShaderSource::Wgsl(
+            concat!(include_str!("output.wgsl"), include_str!("rectangle.wgsl")).into(),
+        ),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -47,11 +50,14 @@ pub(crate) fn create_pipeline(
             module: &shader,
             entry_point: None,
             targets: &[Some(wgpu::ColorTargetState {
-                format: renderer::TEXTURE_FORMAT,
+                format,
                 blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                 write_mask: wgpu::ColorWrites::ALL,
             })],
-            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &renderer::shader_constants(format),
+                ..Default::default()
+            },
         }),
         primitive: wgpu::PrimitiveState {
             topology: wgpu::PrimitiveTopology::TriangleList,
@@ -170,7 +176,6 @@ impl RectangleBatch {
             renderer.buffers.index_offset,
             indices,
         );
-        render_pass.set_bind_group(0, &renderer.uniforms.bind_group, &[]);
         render_pass.set_pipeline(&renderer.rectangle_pipeline);
         render_pass.set_vertex_buffer(
             0,
@@ -25,5 +25,5 @@ fn vertex(input: VertexInput) -> VertexOutput {
 
 @fragment
 fn fragment(input: VertexOutput) -> @location(0) vec4<f32> {
-    return input.color;
+    return output(input.color);
 }
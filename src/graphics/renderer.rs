@@ -1,8 +1,74 @@
+use std::collections::HashMap;
+
 use zerocopy::IntoBytes;
 
-use super::{rectangle, sprite};
+use crate::{Error, image::Image};
+
+use super::{canvas, rectangle, sprite};
+
+pub(crate) struct Config {
+    pub(crate) backends: wgpu::Backends,
+    pub(crate) power_preference: wgpu::PowerPreference,
+    pub(crate) force_fallback_adapter: bool,
+    pub(crate) features: wgpu::Features,
+    pub(crate) limits: wgpu::Limits,
+    pub(crate) virtual_resolution: Option<canvas::VirtualResolution>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            features: wgpu::Features::empty(),
+            limits: wgpu::Limits::default(),
+            virtual_resolution: None,
+        }
+    }
+}
 
-pub(crate) const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+impl Config {
+    pub(crate) fn create_instance(&self) -> wgpu::Instance {
+        wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn request_adapter(
+        &self,
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface>,
+        force_fallback_adapter: bool,
+    ) -> Option<wgpu::Adapter> {
+        block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: self.power_preference,
+            force_fallback_adapter,
+            compatible_surface: surface,
+        }))
+    }
+}
+
+const TEXTURE_FORMATS: [wgpu::TextureFormat; 4] = [
+    wgpu::TextureFormat::Bgra8UnormSrgb,
+    wgpu::TextureFormat::Rgba8UnormSrgb,
+    wgpu::TextureFormat::Bgra8Unorm,
+    wgpu::TextureFormat::Rgba8Unorm,
+];
+
+pub(crate) fn select_format(formats: &[wgpu::TextureFormat]) -> Option<wgpu::TextureFormat> {
+    TEXTURE_FORMATS
+        .into_iter()
+        .find(|format| formats.contains(format))
+}
+
+pub(crate) fn shader_constants(format: wgpu::TextureFormat) -> HashMap<String, f64> {
+    HashMap::from([(
+        "encode_srgb".into(),
+        if format.is_srgb() { 0.0 } else { 1.0 },
+    )])
+}
 
 #[derive(Clone, Copy, PartialEq)]
 pub(crate) struct Color {
@@ -23,6 +89,25 @@ impl From<Color> for wgpu::Color {
     }
 }
 
+impl Color {
+    fn encode_srgb(self) -> Self {
+        fn encode(value: f32) -> f32 {
+            if value <= 0.0031308 {
+                value * 12.92
+            } else {
+                1.055 * value.powf(1.0 / 2.4) - 0.055
+            }
+        }
+
+        Self {
+            r: encode(self.r),
+            g: encode(self.g),
+            b: encode(self.b),
+            a: self.a,
+        }
+    }
+}
+
 impl Default for Color {
     fn default() -> Self {
         Self {
@@ -111,35 +196,53 @@ impl Uniforms {
 }
 
 pub(crate) struct Renderer {
+    pub(crate) format: wgpu::TextureFormat,
+    pub(crate) adapter: wgpu::Adapter,
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
     pub(crate) buffers: Buffers,
-    pub(crate) uniforms: Uniforms,
+    pub(crate) uniform_layout: wgpu::BindGroupLayout,
     pub(crate) rectangle_pipeline: wgpu::RenderPipeline,
     pub(crate) sprite_renderer: sprite::SpriteRenderer,
+    pub(crate) canvas: Option<canvas::Canvas>,
 }
 
 impl Renderer {
-    pub(crate) fn new(adapter: wgpu::Adapter) -> Option<Self> {
-        let (device, queue) = create_device(&adapter)?;
+    pub(crate) fn new(
+        adapter: wgpu::Adapter,
+        format: wgpu::TextureFormat,
+        config: &Config,
+    ) -> Result<Self, Error> {
+        let (device, queue) = create_device(&adapter, config)?;
 
         let buffers = Buffers::new(&device);
 
         let uniform_layout = Uniforms::create_layout(&device);
 
-        let uniforms = Uniforms::new(&uniform_layout, &device);
+        let rectangle_pipeline = rectangle::create_pipeline(&uniform_layout, format, &device);
 
-        let rectangle_pipeline = rectangle::create_pipeline(&uniform_layout, &device);
+        let sprite_renderer = sprite::SpriteRenderer::new(&uniform_layout, format, &device, &queue);
 
-        let sprite_renderer = sprite::SpriteRenderer::new(&uniform_layout, &device, &queue);
+        let canvas = config.virtual_resolution.map(|resolution| {
+            let uniforms = Uniforms::new(&uniform_layout, &device);
+            queue.write_buffer(
+                &uniforms.viewport_buffer,
+                0,
+                [resolution.width as f32, resolution.height as f32].as_bytes(),
+            );
+            canvas::Canvas::new(resolution, uniforms, format, &device)
+        });
 
-        Some(Self {
+        Ok(Self {
+            format,
+            adapter,
             device,
             queue,
             buffers,
-            uniforms,
+            uniform_layout,
             rectangle_pipeline,
             sprite_renderer,
+            canvas,
         })
     }
 
@@ -149,43 +252,208 @@ impl Renderer {
         self.buffers.index_offset = 0;
     }
 
-    pub(crate) fn render(&mut self, render_list: &RenderList, target: wgpu::TextureView) {
+    pub(crate) fn render(
+        &mut self,
+        render_list: &RenderList,
+        target: &wgpu::Texture,
+        uniforms: &Uniforms,
+        canvas: bool,
+        capture: bool,
+    ) -> Option<Image> {
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
 
+        let readback = match capture {
+            false => {
+                self.encode_frame(render_list, target, uniforms, canvas, &mut encoder);
+                None
+            }
+            true if target.usage().contains(wgpu::TextureUsages::COPY_SRC) => {
+                self.encode_frame(render_list, target, uniforms, canvas, &mut encoder);
+                Some(self.encode_readback(target, &mut encoder))
+            }
+            true => {
+                // The target cannot be copied from, so the frame is drawn to a texture
+                // that can, which is then drawn to the target.
+                let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: target.size(),
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING
+                        | wgpu::TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                });
+                self.encode_frame(render_list, &texture, uniforms, canvas, &mut encoder);
+                canvas::Blit::new(
+                    &texture,
+                    wgpu::FilterMode::Nearest,
+                    self.format,
+                    &self.device,
+                )
+                .encode(
+                    [0.0, 0.0, target.width() as f32, target.height() as f32],
+                    target,
+                    &self.queue,
+                    &mut encoder,
+                );
+                Some(self.encode_readback(&texture, &mut encoder))
+            }
+        };
+
+        self.queue.submit(Some(encoder.finish()));
+
+        readback.and_then(|readback| self.read(readback))
+    }
+
+    fn encode_frame(
+        &mut self,
+        render_list: &RenderList,
+        target: &wgpu::Texture,
+        uniforms: &Uniforms,
+        canvas: bool,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        match self.canvas.take_if(|_| canvas) {
+            Some(canvas) => {
+                self.encode_render_pass(render_list, &canvas.texture, &canvas.uniforms, encoder);
+                canvas.encode_blit(target, &self.queue, encoder);
+                self.canvas = Some(canvas);
+            }
+            None => self.encode_render_pass(render_list, target, uniforms, encoder),
+        }
+    }
+
+    fn encode_render_pass(
+        &mut self,
+        render_list: &RenderList,
+        target: &wgpu::Texture,
+        uniforms: &Uniforms,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let clear_color = if self.format.is_srgb() {
+            render_list.clear_color
+        } else {
+            render_list.clear_color.encode_srgb()
+        };
+
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &target,
+                view: &view,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(render_list.clear_color.into()),
+                    load: wgpu::LoadOp::Clear(clear_color.into()),
                     store: wgpu::StoreOp::Store,
                 },
             })],
             ..Default::default()
         });
 
+        render_pass.set_bind_group(0, &uniforms.bind_group, &[]);
+
         for command in render_list.commands.iter() {
             match command {
                 RenderCommand::RectangleBatch(batch) => batch.render(self, &mut render_pass),
                 RenderCommand::SpriteBatch(batch) => batch.render(self, &mut render_pass),
             }
         }
-
-        drop(render_pass);
-        self.queue.submit(Some(encoder.finish()));
     }
 
-    pub(crate) fn set_viewport_uniform(&self, width: f32, height: f32) {
-        self.queue.write_buffer(
-            &self.uniforms.viewport_buffer,
-            0,
-            [width, height].as_bytes(),
+    fn encode_readback(
+        &self,
+        texture: &wgpu::Texture,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Readback {
+        let width = texture.width();
+        let height = texture.height();
+        let bytes_per_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: bytes_per_row as wgpu::BufferAddress * height as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
         );
+
+        Readback {
+            buffer,
+            width,
+            height,
+            bytes_per_row,
+        }
+    }
+
+    fn read(&self, readback: Readback) -> Option<Image> {
+        let slice = readback.buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().ok()?.ok()?;
+
+        let row_size = readback.width as usize * 4;
+        let mut data = Vec::with_capacity(row_size * readback.height as usize);
+        for row in slice
+            .get_mapped_range()
+            .chunks_exact(readback.bytes_per_row as usize)
+        {
+            data.extend_from_slice(&row[..row_size]);
+        }
+        readback.buffer.unmap();
+
+        if matches!(
+            self.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in data.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        Some(Image {
+            data: data.into_boxed_slice(),
+            width: readback.width,
+            height: readback.height,
+        })
+    }
+
+    pub(crate) fn create_uniforms(&self) -> Uniforms {
+        Uniforms::new(&self.uniform_layout, &self.device)
+    }
+
+    pub(crate) fn set_viewport_uniform(&self, uniforms: &Uniforms, width: f32, height: f32) {
+        self.queue
+            .write_buffer(&uniforms.viewport_buffer, 0, [width, height].as_bytes());
     }
 }
 
+struct Readback {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    bytes_per_row: u32,
+}
+
 #[derive(Default)]
 pub(crate) struct RenderList {
     pub(crate) clear_color: Color,
@@ -197,7 +465,10 @@ pub(crate) enum RenderCommand {
     SpriteBatch(sprite::SpriteBatch),
 }
 
-fn create_device(adapter: &wgpu::Adapter) -> Option<(wgpu::Device, wgpu::Queue)> {
+pub(crate) fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
     struct Waker(std::thread::Thread);
 
     impl std::task::Wake for Waker {
@@ -206,15 +477,28 @@ fn create_device(adapter: &wgpu::Adapter) -> Option<(wgpu::Device, wgpu::Queue)>
         }
     }
 
-    let future = adapter.request_device(&wgpu::DeviceDescriptor::default(), None);
     let mut future = std::pin::pin!(future);
     let thread = std::thread::current();
     let waker = std::sync::Arc::new(Waker(thread)).into();
     let mut context = std::task::Context::from_waker(&waker);
     loop {
         match future.as_mut().poll(&mut context) {
-            std::task::Poll::Ready(result) => break result.ok(),
+            std::task::Poll::Ready(result) => break result,
             std::task::Poll::Pending => std::thread::park(),
         }
     }
 }
+
+fn create_device(
+    adapter: &wgpu::Adapter,
+    config: &Config,
+) -> Result<(wgpu::Device, wgpu::Queue), wgpu::RequestDeviceError> {
+    block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            required_features: config.features & adapter.features(),
+            required_limits: config.limits.clone(),
+            ..Default::default()
+        },
+        None,
+    ))
+}
@@ -2,7 +2,7 @@ mod sheet_assembly;
 
 use std::{
     sync::Arc,
-    thread::{self, Thread},
+    thread::{self, JoinHandle},
 };
 
 use sheet_assembly::{AssemblyReceiver, AssemblySender, SheetAssembler, SpriteData};
@@ -100,7 +100,7 @@ impl Texture {
                 view_formats: &[],
             },
             wgpu::util::TextureDataOrder::default(),
-            &data,
+            data,
         );
 
         let bind_group = Self::create_bind_group(&texture, sampler, bind_layout, device);
@@ -130,7 +130,7 @@ pub(crate) type Handle = usize;
 pub(crate) struct SpriteRenderer {
     sprites: Vec<Sprite>,
     spritesheet: Arc<Texture>,
-    assembly_thread: Thread,
+    assembly_thread: Option<JoinHandle<()>>,
     assembly_sender: AssemblySender,
     assembly_receiver: AssemblyReceiver,
     bind_layout: wgpu::BindGroupLayout,
@@ -141,6 +141,7 @@ pub(crate) struct SpriteRenderer {
 impl SpriteRenderer {
     pub(crate) fn new(
         uniform_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
     ) -> Self {
@@ -168,7 +169,9 @@ impl SpriteRenderer {
 
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(include_str!("sprite.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(
+                concat!(include_str!("output.wgsl"), include_str!("sprite.wgsl")).into(),
+            ),
         });
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -203,9 +206,12 @@ impl SpriteRenderer {
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: None,
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &renderer::shader_constants(format),
+                    ..Default::default()
+                },
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: renderer::TEXTURE_FORMAT,
+                    format,
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -229,14 +235,12 @@ impl SpriteRenderer {
 
         let (sheet_assembler, assembly_sender, assembly_receiver) =
             SheetAssembler::new(&sampler, &bind_layout, device, queue);
-        let assembly_thread = thread::spawn(move || sheet_assembler.work())
-            .thread()
-            .clone();
+        let assembly_thread = thread::spawn(move || sheet_assembler.work());
 
         Self {
             sprites: Vec::new(),
             spritesheet: Arc::new(Texture::empty(1, 1, &sampler, &bind_layout, device)),
-            assembly_thread,
+            assembly_thread: Some(assembly_thread),
             assembly_sender,
             assembly_receiver,
             bind_layout,
@@ -246,8 +250,10 @@ impl SpriteRenderer {
     }
 
     pub(crate) fn poll(&mut self) {
-        if !self.assembly_sender.is_empty() {
-            self.assembly_thread.unpark();
+        if let Some(thread) = &self.assembly_thread
+            && !self.assembly_sender.is_empty()
+        {
+            thread.thread().unpark();
         }
 
         if let Ok(feedback) = self.assembly_receiver.try_recv() {
@@ -287,6 +293,18 @@ impl SpriteRenderer {
     }
 }
 
+impl Drop for SpriteRenderer {
+    fn drop(&mut self) {
+        // Disconnecting both channels makes the assembler return once it wakes up.
+        self.assembly_sender = crossbeam::channel::bounded(0).0;
+        self.assembly_receiver = crossbeam::channel::never();
+        if let Some(thread) = self.assembly_thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
 pub(crate) struct SpriteDrawInfo {
     pub(crate) handle: Handle,
     pub(crate) x: f32,
@@ -418,7 +436,6 @@ impl SpriteBatch {
             renderer.buffers.index_offset,
             indices,
         );
-        render_pass.set_bind_group(0, &renderer.uniforms.bind_group, &[]);
         render_pass.set_bind_group(1, &self.texture.bind_group, &[]);
         render_pass.set_pipeline(&renderer.sprite_renderer.pipeline);
         render_pass.set_vertex_buffer(
@@ -28,5 +28,5 @@ fn vertex(input: VertexInput) -> VertexOutput {
 
 @fragment
 fn fragment(input: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(texture, texture_sampler, input.tex_coords);
+    return output(textureSample(texture, texture_sampler, input.tex_coords));
 }
@@ -50,16 +50,22 @@ impl SheetAssembler {
         )
     }
 
-    pub(crate) fn work(self) -> ! {
+    pub(crate) fn work(self) {
         let mut layout = Node::empty();
         let mut sheet = Box::from([]);
         let mut sheet_size = 0;
         loop {
             thread::park();
 
-            let mut allocations = Vec::with_capacity(self.receiver.len());
-            let mut datas = Vec::with_capacity(self.receiver.len());
-            for sprite in self.receiver.try_iter() {
+            let first = match self.receiver.try_recv() {
+                Ok(sprite) => sprite,
+                Err(crossbeam::channel::TryRecvError::Empty) => continue,
+                Err(crossbeam::channel::TryRecvError::Disconnected) => return,
+            };
+
+            let mut allocations = Vec::with_capacity(self.receiver.len() + 1);
+            let mut datas = Vec::with_capacity(self.receiver.len() + 1);
+            for sprite in std::iter::once(first).chain(self.receiver.try_iter()) {
                 allocations.push((sprite.handle, layout.alloc(sprite.width, sprite.height)));
                 datas.push(sprite.data);
             }
@@ -92,10 +98,13 @@ impl SheetAssembler {
                 &self.queue,
             ));
 
-            let _ = self.sender.send(SheetAssemblyFeedback {
+            let feedback = SheetAssemblyFeedback {
                 updated_sprites: allocations,
                 new_sheet: sheet_texture,
-            });
+            };
+            if self.sender.send(feedback).is_err() {
+                return;
+            }
         }
     }
 }
@@ -3,6 +3,7 @@
 /// Raw image data.
 ///
 /// See [Sprite][crate::graphics::Sprite] for drawing the image.
+#[derive(Clone)]
 pub struct Image {
     pub(crate) data: Box<[u8]>,
     pub(crate) width: u32,
@@ -50,6 +51,28 @@ impl Image {
         }
     }
 
+    /// Writes the `Image` as png data.
+    pub fn write_png<W>(&self, destination: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        let mut encoder = png::Encoder::new(destination, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Writes the `Image` to a png file at `path`, replacing it if it already exists.
+    pub fn save_png<P>(&self, path: P) -> std::io::Result<()>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        self.write_png(std::io::BufWriter::new(std::fs::File::create(path)?))
+    }
+
     /// A slice to the underlying data.
     pub fn data(&self) -> &[u8] {
         &self.data
@@ -1,79 +1,268 @@
-use std::sync::{Arc, Once};
+use std::{
+    cell::RefCell,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::{executor, graphics, keyboard, mouse};
+use crate::{Error, executor, graphics, keyboard, mouse, window};
 
-pub(crate) struct Config {
+pub(crate) struct WindowConfig {
+    pub name: &'static str,
     pub title: Option<String>,
+    pub size: Option<(u32, u32)>,
+    pub min_size: Option<(u32, u32)>,
+    pub max_size: Option<(u32, u32)>,
+    pub position: Option<(i32, i32)>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub fullscreen: Option<window::Fullscreen>,
+    pub present_mode: graphics::PresentMode,
+}
+
+impl WindowConfig {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            title: None,
+            size: None,
+            min_size: None,
+            max_size: None,
+            position: None,
+            resizable: true,
+            decorations: true,
+            fullscreen: None,
+            present_mode: graphics::PresentMode::Vsync,
+        }
+    }
+}
+
+pub(crate) struct Config {
+    pub window: WindowConfig,
+    pub windows: Vec<WindowConfig>,
+    pub physical_pixels: bool,
+    pub max_fps: Option<u32>,
+    pub redraw_on_demand: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self { title: None }
+        Self {
+            window: WindowConfig::new(window::MAIN),
+            windows: Vec::new(),
+            physical_pixels: false,
+            max_fps: None,
+            redraw_on_demand: false,
+        }
     }
 }
 
-static ONCE: Once = Once::new();
+thread_local! {
+    // winit only allows one event loop per process, so it is kept for later runs.
+    static EVENT_LOOP: RefCell<Option<winit::event_loop::EventLoop<()>>> =
+        const { RefCell::new(None) };
+}
 
-pub(crate) fn run(config: Config, executor: executor::Executor) {
-    ONCE.call_once(|| {
-        let event_loop = winit::event_loop::EventLoop::builder().build().unwrap();
-        event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
-        let mut app = App::new(config, executor);
-        let _ = event_loop.run_app(&mut app);
-    });
+pub(crate) fn run(
+    config: Config,
+    renderer_config: graphics::renderer::Config,
+    executor: executor::Executor,
+) -> Result<(), Error> {
+    EVENT_LOOP.with_borrow_mut(|event_loop| {
+        if event_loop.is_none() {
+            *event_loop = Some(winit::event_loop::EventLoop::builder().build()?);
+        }
+        let mut app = App::new(config, renderer_config, executor);
+        run_app(event_loop, &mut app)?;
+        app.result
+    })
+}
+
+#[cfg(not(any(target_os = "ios", target_family = "wasm")))]
+fn run_app(
+    event_loop: &mut Option<winit::event_loop::EventLoop<()>>,
+    app: &mut App,
+) -> Result<(), winit::error::EventLoopError> {
+    use winit::platform::run_on_demand::EventLoopExtRunOnDemand;
+
+    let event_loop = event_loop.as_mut().unwrap();
+    event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
+    event_loop.run_app_on_demand(app)
+}
+
+#[cfg(any(target_os = "ios", target_family = "wasm"))]
+fn run_app(
+    event_loop: &mut Option<winit::event_loop::EventLoop<()>>,
+    app: &mut App,
+) -> Result<(), winit::error::EventLoopError> {
+    let event_loop = event_loop.take().unwrap();
+    event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
+    event_loop.run_app(app)
 }
 
 pub(crate) struct App {
     config: Config,
-    internals: Option<Internals>,
+    renderer_config: graphics::renderer::Config,
+    windows: Vec<Internals>,
     executor: executor::Executor,
+    result: Result<(), Error>,
+    next_frame: Instant,
+    redraw_requested: bool,
 }
 
 impl App {
-    pub(crate) fn new(config: Config, executor: executor::Executor) -> Self {
+    pub(crate) fn new(
+        config: Config,
+        renderer_config: graphics::renderer::Config,
+        executor: executor::Executor,
+    ) -> Self {
         Self {
             config,
-            internals: None,
+            renderer_config,
+            windows: Vec::new(),
             executor,
+            result: Ok(()),
+            next_frame: Instant::now(),
+            redraw_requested: true,
         }
     }
-}
 
-impl winit::application::ApplicationHandler for App {
-    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        if let Some(internals) = Internals::new(&mut self.config, event_loop) {
-            self.internals = Some(internals);
-            self.executor.load();
-        } else {
+    fn create_windows(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+    ) -> Result<(), Error> {
+        let instance = self.renderer_config.create_instance();
+
+        window::state::set_physical_pixels(self.config.physical_pixels);
+
+        let window = create_window(&mut self.config.window, event_loop)?;
+
+        let surface = instance.create_surface(window.clone())?;
+
+        let adapter = self
+            .renderer_config
+            .request_adapter(
+                &instance,
+                Some(&surface),
+                self.renderer_config.force_fallback_adapter,
+            )
+            .ok_or(Error::NoAdapter)?;
+
+        let format = graphics::renderer::select_format(&surface.get_capabilities(&adapter).formats)
+            .ok_or(Error::UnsupportedSurfaceFormat)?;
+
+        let renderer = graphics::renderer::Renderer::new(adapter, format, &self.renderer_config)?;
+
+        graphics::context::init(renderer);
+
+        self.windows
+            .push(Internals::new(&self.config.window, window, surface)?);
+
+        for config in &mut self.config.windows {
+            let window = create_window(config, event_loop)?;
+            let surface = instance.create_surface(window.clone())?;
+            self.windows.push(Internals::new(config, window, surface)?);
+        }
+
+        Ok(())
+    }
+
+    fn close_window(
+        &mut self,
+        name: &'static str,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+    ) {
+        if name == window::MAIN {
             event_loop.exit();
+        } else {
+            self.windows.retain(|internals| internals.name != name);
+            window::state::remove(name);
         }
     }
 
-    fn window_event(
+    fn handle_window_event(
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
-        _: winit::window::WindowId,
+        index: usize,
         event: winit::event::WindowEvent,
     ) {
-        let Some(internals) = self.internals.as_mut() else {
-            return;
-        };
+        let internals = &mut self.windows[index];
         match event {
             winit::event::WindowEvent::RedrawRequested => {
+                if internals.minimized {
+                    return;
+                }
+                let surface_texture = match internals.surface.get_current_texture() {
+                    Ok(surface_texture) => surface_texture,
+                    Err(wgpu::SurfaceError::Lost) => {
+                        internals.configure();
+                        self.executor.surface_event(graphics::SurfaceEvent::Lost);
+                        return;
+                    }
+                    Err(wgpu::SurfaceError::Outdated) => {
+                        internals.configure();
+                        self.executor
+                            .surface_event(graphics::SurfaceEvent::Outdated);
+                        return;
+                    }
+                    Err(wgpu::SurfaceError::OutOfMemory) => {
+                        self.result = Err(Error::OutOfMemory);
+                        event_loop.exit();
+                        return;
+                    }
+                    Err(_) => return,
+                };
                 graphics::context::reset();
-                self.executor.draw();
-                if let Ok(surface_texture) = internals.surface.get_current_texture() {
-                    let view = surface_texture
-                        .texture
-                        .create_view(&wgpu::TextureViewDescriptor::default());
-                    graphics::context::render(view);
-                    surface_texture.present();
+                self.executor.draw(internals.name);
+                let screenshot =
+                    graphics::context::render(&surface_texture.texture, &internals.uniforms);
+                surface_texture.present();
+                if let Some(image) = screenshot {
+                    self.executor.screenshot(&image);
+                }
+            }
+
+            winit::event::WindowEvent::Resized(size) => {
+                if let Some(event) = internals.resize(size.width, size.height) {
+                    self.executor.surface_event(event);
+                }
+                if size.width != 0 && size.height != 0 {
+                    let size = size.to_logical(window::state::scale_factor(&internals.window));
+                    self.executor.resized(size.width, size.height);
                 }
             }
 
-            winit::event::WindowEvent::Resized(size) => internals.resize(size.width, size.height),
+            winit::event::WindowEvent::Focused(focused) => {
+                // Buttons released while the window is unfocused never arrive.
+                if !focused {
+                    mouse::state::release_captured();
+                }
+                self.executor.focus_changed(focused);
+            }
 
-            winit::event::WindowEvent::CloseRequested => event_loop.exit(),
+            winit::event::WindowEvent::CursorEntered { .. } => {
+                // Movement is only measured within a window, so entering one does not count.
+                internals.cursor = None;
+            }
+
+            winit::event::WindowEvent::CursorLeft { .. } => {
+                mouse::state::set_hovered(false);
+            }
+
+            winit::event::WindowEvent::Occluded(occluded) => {
+                self.executor.visibility_changed(!occluded);
+            }
+
+            winit::event::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                internals.update_viewport();
+                self.executor.scale_factor_changed(scale_factor);
+            }
+
+            winit::event::WindowEvent::CloseRequested => {
+                let name = internals.name;
+                if name != window::MAIN || self.executor.quit() {
+                    self.close_window(name, event_loop);
+                }
+            }
 
             winit::event::WindowEvent::KeyboardInput {
                 event:
@@ -95,14 +284,24 @@ impl winit::application::ApplicationHandler for App {
             }
 
             winit::event::WindowEvent::CursorMoved { position, .. } => {
-                let (x_0, y_0) = mouse::state::get_position();
-                mouse::state::set_position(position.x as f32, position.y as f32);
-                self.executor.mouse_moved(
-                    position.x as f32,
-                    position.y as f32,
-                    position.x as f32 - x_0,
-                    position.y as f32 - y_0,
-                );
+                let (x, y) = match self.renderer_config.virtual_resolution {
+                    Some(resolution) if internals.name == window::MAIN => resolution.map_position(
+                        position.x as f32,
+                        position.y as f32,
+                        internals.surface_config.width,
+                        internals.surface_config.height,
+                    ),
+                    _ => position
+                        .to_logical::<f32>(window::state::scale_factor(&internals.window))
+                        .into(),
+                };
+                let (dx, dy) = match internals.cursor {
+                    Some((x_0, y_0)) => (x - x_0, y - y_0),
+                    None => (0.0, 0.0),
+                };
+                internals.cursor = Some((x, y));
+                mouse::state::set_position(x, y);
+                self.executor.mouse_moved(x, y, dx, dy);
             }
 
             winit::event::WindowEvent::MouseInput {
@@ -119,7 +318,8 @@ impl winit::application::ApplicationHandler for App {
                     winit::event::MouseButton::Middle => mouse::Button::Middle,
                     _ => unreachable!(),
                 };
-                let (x, y) = mouse::state::get_position();
+                let (x, y) = internals.cursor.unwrap_or((f32::NAN, f32::NAN));
+                mouse::state::set_position(x, y);
                 mouse::state::set_button(button, state.is_pressed());
                 if state.is_pressed() {
                     self.executor.mouse_pressed(x, y, button);
@@ -131,83 +331,260 @@ impl winit::application::ApplicationHandler for App {
             _ => {}
         }
     }
+}
+
+impl winit::application::ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        match self.create_windows(event_loop) {
+            Ok(()) => self.executor.load(),
+            Err(error) => {
+                self.result = Err(error);
+                event_loop.exit();
+            }
+        }
+    }
 
-    fn about_to_wait(&mut self, _: &winit::event_loop::ActiveEventLoop) {
-        self.executor.update();
-        let Some(internals) = self.internals.as_mut() else {
+    fn window_event(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        window_id: winit::window::WindowId,
+        event: winit::event::WindowEvent,
+    ) {
+        let Some(index) = self
+            .windows
+            .iter()
+            .position(|internals| internals.window.id() == window_id)
+        else {
             return;
         };
-        internals.window.request_redraw();
+        if event != winit::event::WindowEvent::RedrawRequested {
+            self.redraw_requested = true;
+        }
+        window::state::with_current(self.windows[index].name, || {
+            self.handle_window_event(event_loop, index, event)
+        });
+    }
+
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let now = Instant::now();
+        let redraw = if self.config.redraw_on_demand {
+            self.redraw_requested |= graphics::context::take_redraw_request();
+            self.redraw_requested && now >= self.next_frame
+        } else {
+            now >= self.next_frame
+        };
+        let frame = redraw && self.windows.iter().any(|internals| !internals.minimized);
+
+        self.executor.update(frame);
+        for name in window::state::take_close_requests() {
+            self.close_window(name, event_loop);
+        }
+        if event_loop.exiting() {
+            return;
+        }
+
+        if redraw {
+            self.redraw_requested = false;
+            if let Some(max_fps) = self.config.max_fps {
+                let interval = Duration::from_secs(1) / max_fps;
+                self.next_frame = (self.next_frame + interval).max(now);
+            }
+            for internals in &self.windows {
+                if !internals.minimized {
+                    internals.window.request_redraw();
+                }
+            }
+        }
+        if self.config.redraw_on_demand {
+            // Catches redraws requested by the update functions that just ran.
+            self.redraw_requested |= graphics::context::take_redraw_request();
+        }
+
+        event_loop.set_control_flow(match (self.config.redraw_on_demand, self.config.max_fps) {
+            (false, None) => winit::event_loop::ControlFlow::Poll,
+            (false, Some(_)) => winit::event_loop::ControlFlow::WaitUntil(
+                self.executor.next_update().min(self.next_frame),
+            ),
+            (true, _) if self.redraw_requested => winit::event_loop::ControlFlow::WaitUntil(
+                self.executor.next_update().min(self.next_frame),
+            ),
+            (true, _) => winit::event_loop::ControlFlow::WaitUntil(self.executor.next_update()),
+        });
+    }
+
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.executor.exit();
+        self.windows.clear();
     }
 }
 
 struct Internals {
+    name: &'static str,
     window: Arc<winit::window::Window>,
     surface: wgpu::Surface<'static>,
     surface_config: wgpu::SurfaceConfiguration,
+    uniforms: graphics::renderer::Uniforms,
+    cursor: Option<(f32, f32)>,
+    minimized: bool,
 }
 
 impl Internals {
-    fn new(config: &mut Config, event_loop: &winit::event_loop::ActiveEventLoop) -> Option<Self> {
-        let attributes = winit::window::Window::default_attributes()
-            .with_title(config.title.take().unwrap_or("heart".into()));
-        let window = Arc::new(event_loop.create_window(attributes).ok()?);
-
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
-
-        let surface = instance.create_surface(window.clone()).ok()?;
-
-        let adapter = instance
-            .enumerate_adapters(wgpu::Backends::all())
-            .into_iter()
-            .filter(|adapter| adapter.is_surface_supported(&surface))
-            .next()?;
+    fn new(
+        config: &WindowConfig,
+        window: Arc<winit::window::Window>,
+        surface: wgpu::Surface<'static>,
+    ) -> Result<Self, Error> {
+        let context = graphics::context::get();
+        let renderer = &context.renderer;
 
         let width = window.inner_size().width;
         let height = window.inner_size().height;
 
-        if !surface
-            .get_capabilities(&adapter)
-            .formats
-            .contains(&wgpu::TextureFormat::Bgra8UnormSrgb)
-        {
-            return None;
+        let capabilities = surface.get_capabilities(&renderer.adapter);
+        if !capabilities.formats.contains(&renderer.format) {
+            return Err(Error::UnsupportedSurfaceFormat);
         }
+        let present_mode = match config.present_mode {
+            graphics::PresentMode::Vsync => wgpu::PresentMode::AutoVsync,
+            graphics::PresentMode::Immediate => wgpu::PresentMode::AutoNoVsync,
+            graphics::PresentMode::Mailbox
+                if capabilities
+                    .present_modes
+                    .contains(&wgpu::PresentMode::Mailbox) =>
+            {
+                wgpu::PresentMode::Mailbox
+            }
+            graphics::PresentMode::Mailbox => wgpu::PresentMode::AutoVsync,
+        };
         let surface_config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: graphics::renderer::TEXTURE_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | (capabilities.usages & wgpu::TextureUsages::COPY_SRC),
+            format: renderer.format,
             width,
             height,
-            present_mode: wgpu::PresentMode::Fifo,
+            present_mode,
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
             view_formats: Vec::new(),
             desired_maximum_frame_latency: 2,
         };
 
-        let renderer = graphics::renderer::Renderer::new(adapter)?;
-        renderer.set_viewport_uniform(width as f32, height as f32);
-
         surface.configure(&renderer.device, &surface_config);
 
-        graphics::context::init(renderer);
+        let uniforms = renderer.create_uniforms();
+        let scale_factor = window::state::scale_factor(&window);
+        renderer.set_viewport_uniform(
+            &uniforms,
+            (width as f64 / scale_factor) as f32,
+            (height as f64 / scale_factor) as f32,
+        );
+
+        window::state::insert(config.name, window.clone());
 
-        Some(Self {
+        Ok(Self {
+            name: config.name,
             window,
             surface,
             surface_config,
+            uniforms,
+            cursor: None,
+            minimized: false,
         })
     }
 
-    fn resize(&mut self, width: u32, height: u32) {
-        if width > 0 && height > 0 {
-            self.surface_config.width = width;
-            self.surface_config.height = height;
-            let context = graphics::context::get();
-            self.surface
-                .configure(&context.renderer.device, &self.surface_config);
-            context
-                .renderer
-                .set_viewport_uniform(width as f32, height as f32);
+    fn configure(&mut self) {
+        let context = graphics::context::get();
+        self.surface
+            .configure(&context.renderer.device, &self.surface_config);
+    }
+
+    fn update_viewport(&self) {
+        let scale_factor = window::state::scale_factor(&self.window);
+        graphics::context::get().renderer.set_viewport_uniform(
+            &self.uniforms,
+            (self.surface_config.width as f64 / scale_factor) as f32,
+            (self.surface_config.height as f64 / scale_factor) as f32,
+        );
+    }
+
+    fn resize(&mut self, width: u32, height: u32) -> Option<graphics::SurfaceEvent> {
+        if width == 0 || height == 0 {
+            if self.minimized {
+                return None;
+            }
+            self.minimized = true;
+            return Some(graphics::SurfaceEvent::Minimized);
+        }
+        self.surface_config.width = width;
+        self.surface_config.height = height;
+        self.configure();
+        self.update_viewport();
+        if self.minimized {
+            self.minimized = false;
+            Some(graphics::SurfaceEvent::Restored)
+        } else {
+            None
+        }
+    }
+}
+
+fn create_window(
+    config: &mut WindowConfig,
+    event_loop: &winit::event_loop::ActiveEventLoop,
+) -> Result<Arc<winit::window::Window>, Error> {
+    let mut attributes = winit::window::Window::default_attributes()
+        .with_title(config.title.take().unwrap_or("heart".into()))
+        .with_resizable(config.resizable)
+        .with_decorations(config.decorations);
+    if let Some((width, height)) = config.size {
+        attributes = attributes.with_inner_size(window::state::size(width, height));
+    }
+    if let Some((width, height)) = config.min_size {
+        attributes = attributes.with_min_inner_size(window::state::size(width, height));
+    }
+    if let Some((width, height)) = config.max_size {
+        attributes = attributes.with_max_inner_size(window::state::size(width, height));
+    }
+    if let Some((x, y)) = config.position {
+        attributes = attributes.with_position(window::state::position(x, y));
+    }
+    if let Some(fullscreen) = config.fullscreen {
+        let monitor = event_loop
+            .primary_monitor()
+            .or_else(|| event_loop.available_monitors().next());
+        let size = config.size.map(|(width, height)| {
+            let scale_factor = monitor
+                .as_ref()
+                .map_or(1.0, |monitor| monitor.scale_factor());
+            let size = window::state::size(width, height).to_physical::<u32>(scale_factor);
+            (size.width, size.height)
+        });
+        attributes = attributes.with_fullscreen(create_fullscreen(fullscreen, monitor, size));
+    }
+    Ok(Arc::new(event_loop.create_window(attributes)?))
+}
+
+pub(crate) fn create_fullscreen(
+    fullscreen: window::Fullscreen,
+    monitor: Option<winit::monitor::MonitorHandle>,
+    size: Option<(u32, u32)>,
+) -> Option<winit::window::Fullscreen> {
+    match fullscreen {
+        window::Fullscreen::Borderless => Some(winit::window::Fullscreen::Borderless(monitor)),
+        window::Fullscreen::Exclusive => {
+            let video_modes = monitor?.video_modes().collect::<Vec<_>>();
+            let matching = video_modes
+                .iter()
+                .filter(|mode| Some((mode.size().width, mode.size().height)) == size)
+                .max_by_key(|mode| mode.refresh_rate_millihertz());
+            let largest = video_modes.iter().max_by_key(|mode| {
+                (
+                    mode.size().width * mode.size().height,
+                    mode.refresh_rate_millihertz(),
+                )
+            });
+            Some(winit::window::Fullscreen::Exclusive(
+                matching.or(largest)?.clone(),
+            ))
         }
     }
 }
@@ -6,18 +6,63 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::{keyboard, mouse};
+use crate::{EventResult, events, graphics, image, keyboard, mouse, scene, schedule, time, window};
+
+pub(crate) type Callback = Box<dyn FnMut(&mut State)>;
+pub(crate) type QuitCallback = Box<dyn FnMut(&mut State) -> bool>;
+pub(crate) type KeyCallback = Box<dyn FnMut(&mut State, keyboard::Scancode) -> EventResult>;
+pub(crate) type MouseCallback = Box<dyn FnMut(&mut State, f32, f32, mouse::Button) -> EventResult>;
+pub(crate) type MouseMovedCallback = Box<dyn FnMut(&mut State, f32, f32, f32, f32) -> EventResult>;
+pub(crate) type ResizedCallback = Box<dyn FnMut(&mut State, u32, u32)>;
+pub(crate) type FocusChangedCallback = Box<dyn FnMut(&mut State, bool)>;
+pub(crate) type VisibilityChangedCallback = Box<dyn FnMut(&mut State, bool)>;
+pub(crate) type ScaleFactorChangedCallback = Box<dyn FnMut(&mut State, f64)>;
+pub(crate) type SurfaceCallback = Box<dyn FnMut(&mut State, graphics::SurfaceEvent)>;
+pub(crate) type ScreenshotCallback = Box<dyn FnMut(&mut State, &image::Image)>;
+pub(crate) type EventCallback = Box<dyn FnMut(&mut State, &dyn Any)>;
+
+pub(crate) struct Scheduled<C> {
+    pub(crate) handle: Option<schedule::Handle>,
+    pub(crate) callback: C,
+}
+
+impl<C> Scheduled<C> {
+    pub(crate) fn new(callback: C) -> Self {
+        Self {
+            handle: None,
+            callback,
+        }
+    }
+
+    pub(crate) fn with_handle(handle: schedule::Handle, callback: C) -> Self {
+        Self {
+            handle: Some(handle),
+            callback,
+        }
+    }
+}
 
 pub(crate) struct Config {
     pub(crate) tick_duration: Duration,
-    pub(crate) load: Vec<Box<dyn FnMut(&mut State)>>,
-    pub(crate) update: Vec<Box<dyn FnMut(&mut State)>>,
-    pub(crate) draw: Vec<Box<dyn FnMut(&mut State)>>,
-    pub(crate) key_pressed: Vec<Box<dyn FnMut(&mut State, keyboard::Scancode)>>,
-    pub(crate) key_released: Vec<Box<dyn FnMut(&mut State, keyboard::Scancode)>>,
-    pub(crate) mouse_pressed: Vec<Box<dyn FnMut(&mut State, f32, f32, mouse::Button)>>,
-    pub(crate) mouse_released: Vec<Box<dyn FnMut(&mut State, f32, f32, mouse::Button)>>,
-    pub(crate) mouse_moved: Vec<Box<dyn FnMut(&mut State, f32, f32, f32, f32)>>,
+    pub(crate) timestep_policy: time::TimestepPolicy,
+    pub(crate) load: Vec<Callback>,
+    pub(crate) stages: [Vec<Scheduled<Callback>>; 6],
+    pub(crate) draw: HashMap<&'static str, Vec<Callback>>,
+    pub(crate) quit: Vec<QuitCallback>,
+    pub(crate) exit: Vec<Callback>,
+    pub(crate) key_pressed: Vec<Scheduled<KeyCallback>>,
+    pub(crate) key_released: Vec<Scheduled<KeyCallback>>,
+    pub(crate) mouse_pressed: Vec<Scheduled<MouseCallback>>,
+    pub(crate) mouse_released: Vec<Scheduled<MouseCallback>>,
+    pub(crate) mouse_moved: Vec<Scheduled<MouseMovedCallback>>,
+    pub(crate) resized: Vec<ResizedCallback>,
+    pub(crate) focus_changed: Vec<FocusChangedCallback>,
+    pub(crate) visibility_changed: Vec<VisibilityChangedCallback>,
+    pub(crate) scale_factor_changed: Vec<ScaleFactorChangedCallback>,
+    pub(crate) surface_event: Vec<SurfaceCallback>,
+    pub(crate) screenshot: Vec<ScreenshotCallback>,
+    pub(crate) events: HashMap<TypeId, Vec<Scheduled<EventCallback>>>,
+    pub(crate) scene: Option<Box<dyn scene::Scene>>,
     // pub(crate) wheel_moved: Vec<Box<dyn FnMut(&mut State, f32)>>,
 }
 
@@ -25,14 +70,25 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             tick_duration: calculate_tick_duration(60),
+            timestep_policy: time::TimestepPolicy::Drop,
             load: Vec::new(),
-            update: Vec::new(),
-            draw: Vec::new(),
+            stages: Default::default(),
+            draw: HashMap::new(),
+            quit: Vec::new(),
+            exit: Vec::new(),
             key_pressed: Vec::new(),
             key_released: Vec::new(),
             mouse_pressed: Vec::new(),
             mouse_released: Vec::new(),
             mouse_moved: Vec::new(),
+            resized: Vec::new(),
+            focus_changed: Vec::new(),
+            visibility_changed: Vec::new(),
+            scale_factor_changed: Vec::new(),
+            surface_event: Vec::new(),
+            screenshot: Vec::new(),
+            events: HashMap::new(),
+            scene: None,
             // wheel_moved: Vec::new(),
         }
     }
@@ -62,6 +118,24 @@ impl State {
     {
         self.storage.get_mut(&TypeId::of::<T>())?.downcast_mut()
     }
+
+    pub(crate) fn retrieve_many<const N: usize>(
+        &mut self,
+        types: [(TypeId, &'static str); N],
+    ) -> Option<[&mut dyn Any; N]> {
+        for (index, (id, name)) in types.iter().enumerate() {
+            if types[..index].iter().any(|(other, _)| other == id) {
+                panic!("heart: a function takes the state `{name}` more than once");
+            }
+        }
+        let values = self
+            .storage
+            .get_disjoint_mut(types.each_ref().map(|(id, _)| id));
+        if values.iter().any(Option::is_none) {
+            return None;
+        }
+        Some(values.map(|value| &mut **value.unwrap()))
+    }
 }
 
 pub(crate) fn calculate_tick_duration(tick_rate: u64) -> Duration {
@@ -81,26 +155,67 @@ impl Clock {
         }
     }
 
-    fn tick(&mut self, duration: Duration) -> bool {
-        let now = Instant::now();
-        self.collected += now - self.last;
-        self.last = now;
-        if self.collected > duration {
-            self.collected -= duration;
-            if self.collected > duration {
-                self.collected = Duration::ZERO;
+    fn tick(
+        &mut self,
+        now: Instant,
+        duration: Duration,
+        policy: time::TimestepPolicy,
+        frame: bool,
+    ) -> (u32, Duration) {
+        let elapsed = now - self.last;
+        match policy {
+            time::TimestepPolicy::Drop => {
+                self.last = now;
+                self.collected += elapsed;
+                if self.collected > duration {
+                    self.collected -= duration;
+                    if self.collected > duration {
+                        self.collected = Duration::ZERO;
+                    }
+                    (1, duration)
+                } else {
+                    (0, duration)
+                }
+            }
+            time::TimestepPolicy::CatchUp { max_updates } => {
+                self.last = now;
+                self.collected += elapsed;
+                let mut updates = 0;
+                while self.collected >= duration && updates < max_updates {
+                    self.collected -= duration;
+                    updates += 1;
+                }
+                if self.collected >= duration {
+                    self.collected = Duration::from_nanos(
+                        (self.collected.as_nanos() % duration.as_nanos()) as u64,
+                    );
+                }
+                (updates, duration)
+            }
+            // Without frames, for example while minimized, updates fall back to the tick rate.
+            time::TimestepPolicy::Variable { max_delta } if frame || elapsed >= duration => {
+                self.last = now;
+                (1, elapsed.min(max_delta))
             }
-            true
-        } else {
-            false
+            time::TimestepPolicy::Variable { .. } => (0, Duration::ZERO),
         }
     }
+
+    fn next_tick(&self, duration: Duration) -> Instant {
+        self.last + duration.saturating_sub(self.collected)
+    }
+
+    fn alpha(&self, duration: Duration) -> f32 {
+        (self.collected.as_secs_f32() / duration.as_secs_f32()).min(1.0 - f32::EPSILON)
+    }
 }
 
 pub(crate) struct Executor {
     config: Config,
     state: State,
+    scenes: scene::state::Stack,
     clock: Clock,
+    loaded: bool,
 }
 
 impl Executor {
@@ -108,66 +223,202 @@ impl Executor {
         Self {
             config,
             state: State::new(),
+            scenes: scene::state::Stack::new(),
             clock: Clock::new(),
+            loaded: false,
         }
     }
 
+    pub(crate) fn state(&mut self) -> &mut State {
+        &mut self.state
+    }
+
     pub(crate) fn load(&mut self) {
+        time::state::init(self.config.tick_duration);
         self.config
             .load
             .iter_mut()
             .for_each(|load| load(&mut self.state));
+        if let Some(scene) = self.config.scene.take() {
+            self.scenes.apply(scene::state::Command::Push(scene));
+        }
+        self.scenes.process();
+        self.loaded = true;
     }
 
-    pub(crate) fn draw(&mut self) {
-        self.config
-            .draw
+    fn run_stage(&mut self, stage: schedule::Stage) {
+        schedule::state::apply(&mut self.config);
+        self.config.stages[stage as usize]
             .iter_mut()
-            .for_each(|draw| draw(&mut self.state));
+            .for_each(|scheduled| (scheduled.callback)(&mut self.state));
     }
 
-    pub(crate) fn update(&mut self) {
-        if self.clock.tick(self.config.tick_duration) {
-            self.config
-                .update
-                .iter_mut()
-                .for_each(|update| update(&mut self.state));
+    pub(crate) fn draw(&mut self, name: &str) {
+        if name == window::MAIN {
+            time::state::frame();
+            self.run_stage(schedule::Stage::PreDraw);
+            self.run_stage(schedule::Stage::Draw);
+            self.scenes.draw();
+            self.run_stage(schedule::Stage::Overlay);
+        } else if let Some(draw) = self.config.draw.get_mut(name) {
+            draw.iter_mut().for_each(|draw| draw(&mut self.state));
         }
     }
 
-    pub(crate) fn key_pressed(&mut self, scancode: keyboard::Scancode) {
+    pub(crate) fn update(&mut self, frame: bool) {
+        let (updates, delta) = self.clock.tick(
+            Instant::now(),
+            self.config.tick_duration,
+            self.config.timestep_policy,
+            frame,
+        );
+        for _ in 0..updates {
+            self.step_by(delta);
+        }
+        time::state::set_alpha(self.clock.alpha(self.config.tick_duration));
+    }
+
+    pub(crate) fn next_update(&self) -> Instant {
+        self.clock.next_tick(self.config.tick_duration)
+    }
+
+    pub(crate) fn step(&mut self) {
+        self.step_by(self.config.tick_duration);
+    }
+
+    fn step_by(&mut self, delta: Duration) {
+        time::state::set_update_delta(delta);
+        self.deliver_events();
+        self.run_stage(schedule::Stage::PreUpdate);
+        self.run_stage(schedule::Stage::Update);
+        self.scenes.update(delta);
+        self.run_stage(schedule::Stage::PostUpdate);
+        time::state::tick();
+    }
+
+    fn deliver_events(&mut self) {
+        schedule::state::apply(&mut self.config);
+        for (type_id, event) in events::state::take() {
+            if let Some(callbacks) = self.config.events.get_mut(&type_id) {
+                callbacks
+                    .iter_mut()
+                    .for_each(|scheduled| (scheduled.callback)(&mut self.state, &*event));
+            }
+        }
+    }
+
+    pub(crate) fn quit(&mut self) -> bool {
+        let mut quit = true;
+        for callback in &mut self.config.quit {
+            quit &= callback(&mut self.state);
+        }
+        quit
+    }
+
+    pub(crate) fn exit(&mut self) {
+        if !std::mem::take(&mut self.loaded) {
+            return;
+        }
+        self.scenes.unload();
         self.config
-            .key_pressed
+            .exit
             .iter_mut()
-            .for_each(|key_pressed| key_pressed(&mut self.state, scancode));
+            .for_each(|exit| exit(&mut self.state));
+    }
+
+    pub(crate) fn key_pressed(&mut self, scancode: keyboard::Scancode) {
+        schedule::state::apply(&mut self.config);
+        let handled = self.config.key_pressed.iter_mut().any(|key_pressed| {
+            (key_pressed.callback)(&mut self.state, scancode) == EventResult::Handled
+        });
+        if !handled && let Some(scene) = self.scenes.top() {
+            scene.key_pressed(scancode);
+        }
     }
 
     pub(crate) fn key_released(&mut self, scancode: keyboard::Scancode) {
+        schedule::state::apply(&mut self.config);
+        let handled = self.config.key_released.iter_mut().any(|key_released| {
+            (key_released.callback)(&mut self.state, scancode) == EventResult::Handled
+        });
+        if !handled && let Some(scene) = self.scenes.top() {
+            scene.key_released(scancode);
+        }
+    }
+
+    pub(crate) fn mouse_pressed(&mut self, x: f32, y: f32, button: mouse::Button) {
+        schedule::state::apply(&mut self.config);
+        let handled = self.config.mouse_pressed.iter_mut().any(|mouse_pressed| {
+            (mouse_pressed.callback)(&mut self.state, x, y, button) == EventResult::Handled
+        });
+        mouse::state::set_captured(button, handled);
+        if !handled && let Some(scene) = self.scenes.top() {
+            scene.mouse_pressed(x, y, button);
+        }
+    }
+
+    pub(crate) fn mouse_released(&mut self, x: f32, y: f32, button: mouse::Button) {
+        schedule::state::apply(&mut self.config);
+        let handled = self.config.mouse_released.iter_mut().any(|mouse_released| {
+            (mouse_released.callback)(&mut self.state, x, y, button) == EventResult::Handled
+        });
+        mouse::state::set_captured(button, false);
+        if !handled && let Some(scene) = self.scenes.top() {
+            scene.mouse_released(x, y, button);
+        }
+    }
+
+    pub(crate) fn mouse_moved(&mut self, x: f32, y: f32, dx: f32, dy: f32) {
+        schedule::state::apply(&mut self.config);
+        let handled = self.config.mouse_moved.iter_mut().any(|mouse_moved| {
+            (mouse_moved.callback)(&mut self.state, x, y, dx, dy) == EventResult::Handled
+        });
+        mouse::state::set_hovered(handled);
+        if !handled && let Some(scene) = self.scenes.top() {
+            scene.mouse_moved(x, y, dx, dy);
+        }
+    }
+
+    pub(crate) fn resized(&mut self, width: u32, height: u32) {
         self.config
-            .key_released
+            .resized
             .iter_mut()
-            .for_each(|key_released| key_released(&mut self.state, scancode));
+            .for_each(|resized| resized(&mut self.state, width, height));
     }
 
-    pub(crate) fn mouse_pressed(&mut self, x: f32, y: f32, button: mouse::Button) {
+    pub(crate) fn focus_changed(&mut self, focused: bool) {
         self.config
-            .mouse_pressed
+            .focus_changed
             .iter_mut()
-            .for_each(|mouse_pressed| mouse_pressed(&mut self.state, x, y, button));
+            .for_each(|focus_changed| focus_changed(&mut self.state, focused));
     }
 
-    pub(crate) fn mouse_released(&mut self, x: f32, y: f32, button: mouse::Button) {
+    pub(crate) fn visibility_changed(&mut self, visible: bool) {
         self.config
-            .mouse_released
+            .visibility_changed
             .iter_mut()
-            .for_each(|mouse_released| mouse_released(&mut self.state, x, y, button));
+            .for_each(|visibility_changed| visibility_changed(&mut self.state, visible));
     }
 
-    pub(crate) fn mouse_moved(&mut self, x: f32, y: f32, dx: f32, dy: f32) {
+    pub(crate) fn scale_factor_changed(&mut self, scale_factor: f64) {
+        self.config
+            .scale_factor_changed
+            .iter_mut()
+            .for_each(|scale_factor_changed| scale_factor_changed(&mut self.state, scale_factor));
+    }
+
+    pub(crate) fn surface_event(&mut self, event: graphics::SurfaceEvent) {
         self.config
-            .mouse_moved
+            .surface_event
             .iter_mut()
-            .for_each(|mouse_moved| mouse_moved(&mut self.state, x, y, dx, dy));
+            .for_each(|surface_event| surface_event(&mut self.state, event));
+    }
+
+    pub(crate) fn screenshot(&mut self, image: &image::Image) {
+        self.config
+            .screenshot
+            .iter_mut()
+            .for_each(|screenshot| screenshot(&mut self.state, image));
     }
 
     // pub(crate) fn wheel_moved(&mut self, delta: f32) {
@@ -177,3 +428,71 @@ impl Executor {
     //         .for_each(|wheel_moved| wheel_moved(&mut self.state, delta));
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Clock;
+    use crate::time::TimestepPolicy;
+
+    const TICK: Duration = Duration::from_millis(10);
+
+    fn clock(start: Instant) -> Clock {
+        Clock {
+            last: start,
+            collected: Duration::ZERO,
+        }
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn drop() {
+        let start = Instant::now();
+        let mut clock = clock(start);
+        let policy = TimestepPolicy::Drop;
+
+        assert_eq!(clock.tick(start + ms(6), TICK, policy, true), (0, TICK));
+        assert_eq!(clock.tick(start + ms(12), TICK, policy, true), (1, TICK));
+        assert_eq!(clock.collected, ms(2));
+
+        // Falling more than a tick behind drops the extra time.
+        assert_eq!(clock.tick(start + ms(62), TICK, policy, true), (1, TICK));
+        assert_eq!(clock.collected, Duration::ZERO);
+    }
+
+    #[test]
+    fn catch_up() {
+        let start = Instant::now();
+        let mut clock = clock(start);
+        let policy = TimestepPolicy::CatchUp { max_updates: 3 };
+
+        assert_eq!(clock.tick(start + ms(25), TICK, policy, true), (2, TICK));
+        assert_eq!(clock.collected, ms(5));
+
+        // Beyond `max_updates`, whole ticks are dropped but the remainder is kept.
+        assert_eq!(clock.tick(start + ms(82), TICK, policy, true), (3, TICK));
+        assert_eq!(clock.collected, ms(2));
+    }
+
+    #[test]
+    fn variable() {
+        let start = Instant::now();
+        let mut clock = clock(start);
+        let policy = TimestepPolicy::Variable { max_delta: ms(25) };
+
+        assert_eq!(clock.tick(start + ms(4), TICK, policy, true), (1, ms(4)));
+
+        // Without a frame, updates wait for a whole tick.
+        assert_eq!(
+            clock.tick(start + ms(8), TICK, policy, false),
+            (0, Duration::ZERO)
+        );
+        assert_eq!(clock.tick(start + ms(15), TICK, policy, false), (1, ms(11)));
+
+        assert_eq!(clock.tick(start + ms(115), TICK, policy, true), (1, ms(25)));
+    }
+}
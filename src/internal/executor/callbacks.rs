@@ -6,158 +6,183 @@
 // generic over an `A` parameter, which represents the
 // arguments of the callback function as a tuple. For
 // example, the `UpdateCallback` trait is implemented for
-// two kinds of functions:
+// these kinds of functions:
 // `fn()`
-// `fn(&mut S)`
-// In the first case, the `A` parameter is the empty tuple
-// `()` and in the second case it's `(&mut S,)`. It doesn't
-// actually matter what types are chosen for `A` as long as
-// they are different. However, using the function argument
-// tuple is a sure way to guarantee that each signature gets
-// a unique `UpdateCallback`. The reason for needing them to
+// `fn(P1)`
+// `fn(P1, P2)`
+// and so on, where every `P` is a `Param`, which is either
+// `&mut S` or `&S`. The `A` parameter is then `()`,
+// `(P1,)`, `(P1, P2)` and so on. It doesn't actually matter
+// what types are chosen for `A` as long as they are
+// different. However, using the function argument tuple
+// is a sure way to guarantee that each signature gets a
+// unique `UpdateCallback`. The reason for needing them to
 // be unique is called "coherence". If we tried implementing
 // both types of signatures for the same exact trait, we
 // would get an overlapping implementation error.
-
-use crate::{keyboard, mouse};
+//
+// The `P` types that the compiler infers from a function
+// contain some unknown lifetime, so the function could not
+// be called with the references we get out of `State`.
+// That's why every function must also be callable with
+// `P::Item<'a>` for any lifetime `'a`. Functions taking
+// references are callable with any lifetime, so this costs
+// nothing. The impls for all the different numbers of
+// parameters are generated by the `callback` macro.
+//
+// The return type `R` of a function is the last element
+// of `A` as well, so `A` is actually `(R,)`, `(P1, R)`
+// and so on. Input functions may return either `()` or
+// an `EventResult`, and both need their own impl. Turning
+// `R` into what the executor expects is done by the
+// `IntoOutput` trait.
+//
+// `EventCallback` is additionally generic over the type
+// of event `E`, so that functions taking different events
+// are different callbacks.
+
+use std::any::{Any, TypeId};
+
+use crate::{EventResult, graphics, image, keyboard, mouse};
 
 use super::State;
 
-pub(crate) trait LoadCallback<R> {
-    fn call(&mut self, state: &mut State);
-}
+pub(crate) trait Param {
+    type Target: Any;
+    type Item<'a>;
 
-impl<F, R> LoadCallback<R> for F
-where
-    F: FnMut() -> R,
-    R: 'static,
-{
-    fn call(&mut self, state: &mut State) {
-        state.store(self());
-    }
-}
-
-pub(crate) trait UpdateCallback<A> {
-    fn call(&mut self, state: &mut State);
+    fn item(target: &mut Self::Target) -> Self::Item<'_>;
 }
 
-impl<F> UpdateCallback<()> for F
+impl<S> Param for &mut S
 where
-    F: FnMut(),
+    S: Any,
 {
-    fn call(&mut self, _: &mut State) {
-        self();
+    type Target = S;
+    type Item<'a> = &'a mut S;
+
+    fn item(target: &mut S) -> &mut S {
+        target
     }
 }
 
-impl<F, S> UpdateCallback<(&mut S,)> for F
+impl<S> Param for &S
 where
-    F: FnMut(&mut S),
-    S: 'static,
+    S: Any,
 {
-    fn call(&mut self, state: &mut State) {
-        if let Some(s) = state.retrieve() {
-            self(s);
-        }
+    type Target = S;
+    type Item<'a> = &'a S;
+
+    fn item(target: &mut S) -> &S {
+        target
     }
 }
 
-pub(crate) trait DrawCallback<A> {
-    fn call(&mut self, state: &mut State);
+pub(crate) trait IntoOutput<T> {
+    fn into_output(self) -> T;
 }
 
-impl<F> DrawCallback<()> for F
-where
-    F: FnMut(),
-{
-    fn call(&mut self, _: &mut State) {
-        self();
+impl<T> IntoOutput<T> for T {
+    fn into_output(self) -> T {
+        self
     }
 }
 
-impl<F, S> DrawCallback<(&mut S,)> for F
-where
-    F: FnMut(&mut S),
-    S: 'static,
-{
-    fn call(&mut self, state: &mut State) {
-        if let Some(s) = state.retrieve() {
-            self(s);
-        }
+impl IntoOutput<EventResult> for () {
+    fn into_output(self) -> EventResult {
+        EventResult::Ignored
     }
 }
 
-pub(crate) trait KeyCallback<A> {
-    fn call(&mut self, state: &mut State, scancode: keyboard::Scancode);
+pub(crate) trait LoadCallback<R> {
+    fn call(&mut self, state: &mut State);
 }
 
-impl<F> KeyCallback<(keyboard::Scancode,)> for F
+impl<F, R> LoadCallback<R> for F
 where
-    F: FnMut(keyboard::Scancode),
+    F: FnMut() -> R,
+    R: 'static,
 {
-    fn call(&mut self, _: &mut State, scancode: keyboard::Scancode) {
-        self(scancode);
+    fn call(&mut self, state: &mut State) {
+        state.store(self());
     }
 }
 
-impl<F, S> KeyCallback<(&mut S, keyboard::Scancode)> for F
-where
-    F: FnMut(&mut S, keyboard::Scancode),
-    S: 'static,
-{
-    fn call(&mut self, state: &mut State, scancode: keyboard::Scancode) {
-        if let Some(s) = state.retrieve() {
-            self(s, scancode);
+macro_rules! callback {
+    ($callback:ident$(<$generic:ident>)?($($arg:ident: $arg_type:ty),*)) => {
+        callback!($callback$(<$generic>)?($($arg: $arg_type),*) -> () = ());
+    };
+
+    ($callback:ident$(<$generic:ident>)?($($arg:ident: $arg_type:ty),*) -> $output:ty = $missing:expr) => {
+        pub(crate) trait $callback<$($generic,)? A> {
+            fn call(&mut self, state: &mut State, $($arg: $arg_type),*) -> $output;
         }
-    }
-}
 
-pub(crate) trait MouseCallback<A> {
-    fn call(&mut self, state: &mut State, x: f32, y: f32, button: mouse::Button);
+        callback!(@impl $callback[$($generic)?]($($arg: $arg_type),*) -> $output = $missing;);
+        callback!(@impl $callback[$($generic)?]($($arg: $arg_type),*) -> $output = $missing; P1 p1);
+        callback!(@impl $callback[$($generic)?]($($arg: $arg_type),*) -> $output = $missing; P1 p1, P2 p2);
+        callback!(@impl $callback[$($generic)?]($($arg: $arg_type),*) -> $output = $missing; P1 p1, P2 p2, P3 p3);
+        callback!(@impl $callback[$($generic)?]($($arg: $arg_type),*) -> $output = $missing; P1 p1, P2 p2, P3 p3, P4 p4);
+    };
+
+    (@impl $callback:ident[$($generic:ident)?]($($arg:ident: $arg_type:ty),*) -> $output:ty = $missing:expr; $($param:ident $value:ident),*) => {
+        impl<F, $($generic,)? $($param,)* R> $callback<$($generic,)? ($($param,)* $($arg_type,)* R,)> for F
+        where
+            F: FnMut($($param,)* $($arg_type),*) -> R
+                + for<'a> FnMut($(<$param as Param>::Item<'a>,)* $($arg_type),*) -> R,
+            $($param: Param,)*
+            R: IntoOutput<$output>,
+        {
+            fn call(&mut self, state: &mut State, $($arg: $arg_type),*) -> $output {
+                #[allow(non_snake_case, clippy::too_many_arguments)]
+                fn call_inner<$($generic,)? $($param,)* R>(
+                    mut f: impl FnMut($($param,)* $($arg_type),*) -> R,
+                    $($param: $param,)*
+                    $($arg: $arg_type),*
+                ) -> R {
+                    f($($param,)* $($arg),*)
+                }
+
+                let Some([$($value),*]) = state.retrieve_many([
+                    $((TypeId::of::<$param::Target>(), std::any::type_name::<$param::Target>())),*
+                ]) else {
+                    return $missing;
+                };
+                call_inner(
+                    self,
+                    $($param::item($value.downcast_mut().unwrap()),)*
+                    $($arg),*
+                )
+                .into_output()
+            }
+        }
+    };
 }
 
-impl<F> MouseCallback<(f32, f32, mouse::Button)> for F
-where
-    F: FnMut(f32, f32, mouse::Button),
-{
-    fn call(&mut self, _: &mut State, x: f32, y: f32, button: mouse::Button) {
-        self(x, y, button);
-    }
-}
+callback!(UpdateCallback());
 
-impl<F, S> MouseCallback<(&mut S, f32, f32, mouse::Button)> for F
-where
-    F: FnMut(&mut S, f32, f32, mouse::Button),
-    S: 'static,
-{
-    fn call(&mut self, state: &mut State, x: f32, y: f32, button: mouse::Button) {
-        if let Some(s) = state.retrieve() {
-            self(s, x, y, button);
-        }
-    }
-}
+callback!(DrawCallback());
 
-pub(crate) trait MouseMovedCallback<A> {
-    fn call(&mut self, state: &mut State, x: f32, y: f32, dx: f32, dy: f32);
-}
+callback!(QuitCallback() -> bool = true);
 
-impl<F> MouseMovedCallback<(f32, f32, f32, f32)> for F
-where
-    F: FnMut(f32, f32, f32, f32),
-{
-    fn call(&mut self, _: &mut State, x: f32, y: f32, dx: f32, dy: f32) {
-        self(x, y, dx, dy);
-    }
-}
+callback!(ExitCallback());
 
-impl<F, S> MouseMovedCallback<(&mut S, f32, f32, f32, f32)> for F
-where
-    F: FnMut(&mut S, f32, f32, f32, f32),
-    S: 'static,
-{
-    fn call(&mut self, state: &mut State, x: f32, y: f32, dx: f32, dy: f32) {
-        if let Some(s) = state.retrieve() {
-            self(s, x, y, dx, dy);
-        }
-    }
-}
+callback!(KeyCallback(scancode: keyboard::Scancode) -> EventResult = EventResult::Ignored);
+
+callback!(MouseCallback(x: f32, y: f32, button: mouse::Button) -> EventResult = EventResult::Ignored);
+
+callback!(MouseMovedCallback(x: f32, y: f32, dx: f32, dy: f32) -> EventResult = EventResult::Ignored);
+
+callback!(ResizedCallback(width: u32, height: u32));
+
+callback!(FocusChangedCallback(focused: bool));
+
+callback!(VisibilityChangedCallback(visible: bool));
+
+callback!(ScaleFactorChangedCallback(scale_factor: f64));
+
+callback!(SurfaceCallback(event: graphics::SurfaceEvent));
+
+callback!(ScreenshotCallback(image: &image::Image));
+
+callback!(EventCallback<E>(event: &E));
@@ -0,0 +1,85 @@
+use crate::{Error, executor, graphics, window};
+
+pub(crate) struct Config {
+    pub width: u32,
+    pub height: u32,
+}
+
+pub(crate) struct Headless {
+    texture: wgpu::Texture,
+    uniforms: graphics::renderer::Uniforms,
+}
+
+impl Headless {
+    pub(crate) fn create_renderer(
+        renderer_config: &graphics::renderer::Config,
+    ) -> Result<graphics::renderer::Renderer, Error> {
+        let instance = renderer_config.create_instance();
+
+        let adapter = renderer_config
+            .request_adapter(&instance, None, renderer_config.force_fallback_adapter)
+            .or_else(|| renderer_config.request_adapter(&instance, None, true))
+            .ok_or(Error::NoAdapter)?;
+
+        graphics::renderer::Renderer::new(
+            adapter,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            renderer_config,
+        )
+    }
+
+    pub(crate) fn new(config: &Config) -> Self {
+        let context = graphics::context::get();
+        let renderer = &context.renderer;
+
+        let uniforms = renderer.create_uniforms();
+        renderer.set_viewport_uniform(&uniforms, config.width as f32, config.height as f32);
+
+        let texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: renderer.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        Self { texture, uniforms }
+    }
+
+    pub(crate) fn draw(&self, executor: &mut executor::Executor) {
+        graphics::context::reset();
+        executor.draw(window::MAIN);
+        let screenshot = graphics::context::render(&self.texture, &self.uniforms);
+        graphics::context::get()
+            .renderer
+            .device
+            .poll(wgpu::Maintain::Wait);
+        if let Some(image) = screenshot {
+            executor.screenshot(&image);
+        }
+    }
+}
+
+pub(crate) fn run(
+    config: Config,
+    renderer_config: graphics::renderer::Config,
+    mut executor: executor::Executor,
+) -> Result<(), Error> {
+    graphics::context::init(Headless::create_renderer(&renderer_config)?);
+    window::state::init_headless(config.width, config.height);
+    let headless = Headless::new(&config);
+    executor.load();
+    while !window::state::take_close_requests().contains(&window::MAIN) {
+        executor.step();
+        headless.draw(&mut executor);
+    }
+    executor.exit();
+    Ok(())
+}
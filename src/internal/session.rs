@@ -0,0 +1,51 @@
+use std::{
+    cell::Cell,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{events, graphics, keyboard, mouse, scene, schedule, window};
+
+static SESSION: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    // Locking `SESSION` again on the thread that holds it would never return.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Exclusive use of the global state while [heart][crate] is running.
+///
+/// Beginning a session blocks until the previous one has ended on another thread, and
+/// panics if the current thread already has one. Ending a session drops the graphics
+/// context and forgets the windows, so a new session starts clean.
+pub(crate) struct Session {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Session {
+    pub(crate) fn begin() -> Self {
+        if ACTIVE.get() {
+            panic!("heart: heart is already running on this thread");
+        }
+        let guard = SESSION.lock().unwrap_or_else(PoisonError::into_inner);
+        keyboard::state::init();
+        events::state::init();
+        mouse::state::init();
+        scene::state::init();
+        schedule::state::init();
+        ACTIVE.set(true);
+        Self { _guard: guard }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        graphics::context::deinit();
+        window::state::clear();
+        ACTIVE.set(false);
+    }
+}
+
+/// Returns whether the current thread has a session.
+pub(crate) fn is_active() -> bool {
+    ACTIVE.get()
+}
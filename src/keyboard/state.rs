@@ -1,7 +1,4 @@
-use std::sync::{
-    Arc, OnceLock,
-    atomic::{AtomicBool, Ordering},
-};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use super::Scancode;
 
@@ -11,18 +8,20 @@ struct State {
     keys: [AtomicBool; KEY_COUNT],
 }
 
-static STATE: OnceLock<Arc<State>> = OnceLock::new();
+static STATE: State = State {
+    keys: [const { AtomicBool::new(false) }; KEY_COUNT],
+};
 
 pub(crate) fn init() {
-    let _ = STATE.set(Arc::new(State {
-        keys: [const { AtomicBool::new(false) }; KEY_COUNT],
-    }));
+    for key in &STATE.keys {
+        key.store(false, Ordering::Relaxed);
+    }
 }
 
 pub(crate) fn get_key(key: Scancode) -> bool {
-    STATE.get().unwrap().keys[key as usize].load(Ordering::Relaxed)
+    STATE.keys[key as usize].load(Ordering::Relaxed)
 }
 
 pub(crate) fn set_key(key: Scancode, pressed: bool) {
-    STATE.get().unwrap().keys[key as usize].store(pressed, Ordering::Relaxed);
+    STATE.keys[key as usize].store(pressed, Ordering::Relaxed);
 }
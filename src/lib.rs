@@ -1,11 +1,10 @@
 //! Call [build] to configure and run [heart][crate].
 //!
-//! ```
-//! fn main() {
-//!     heart::build()
-//!         .with_title("heart")
-//!         .run();
-//! }
+//! ```no_run
+//! heart::build()
+//!     .with_title("heart")
+//!     .run()
+//!     .unwrap();
 //! ```
 //!
 //! ❤️
@@ -13,13 +12,36 @@
 mod internal {
     pub(crate) mod app;
     pub(crate) mod executor;
+    pub(crate) mod headless;
+    pub(crate) mod session;
 }
 pub(crate) use internal::*;
 
+mod error;
+pub use error::Error;
+
+/// Re-export of [wgpu], used for configuring the graphics adapter and device.
+pub use wgpu;
+
+pub mod events;
 pub mod graphics;
 pub mod image;
 pub mod keyboard;
 pub mod mouse;
+pub mod scene;
+pub mod schedule;
+pub mod testing;
+pub mod time;
+pub mod window;
+
+/// Stops [heart][crate] once the current callback returns.
+///
+/// Unlike the user closing the [MAIN][window::MAIN] window, this does not call the
+/// [quit][HeartBuilder::with_quit] functions, so it can be used to confirm a cancelled quit.
+/// The [exit][HeartBuilder::with_exit] functions are still called.
+pub fn quit() {
+    window::with_current(window::MAIN, window::close);
+}
 
 /// Returns a builder for configuring and running [heart][crate].
 ///
@@ -34,6 +56,8 @@ pub fn build() -> HeartBuilder {
 /// Call [run][HeartBuilder::run] to consume the builder and run [heart][crate].
 pub struct HeartBuilder {
     app_config: app::Config,
+    headless_config: Option<headless::Config>,
+    renderer_config: graphics::renderer::Config,
     executor_config: executor::Config,
 }
 
@@ -42,6 +66,8 @@ impl HeartBuilder {
     pub fn new() -> Self {
         Self {
             app_config: app::Config::default(),
+            headless_config: None,
+            renderer_config: graphics::renderer::Config::default(),
             executor_config: executor::Config::default(),
         }
     }
@@ -51,7 +77,181 @@ impl HeartBuilder {
     where
         S: Into<String>,
     {
-        self.app_config.title = Some(title.into());
+        self.app_config.window.title = Some(title.into());
+        self
+    }
+
+    /// Sets the initial size of the window in pixels.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.app_config.window.size = Some((width, height));
+        self
+    }
+
+    /// Sets the minimum size of the window in pixels.
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.app_config.window.min_size = Some((width, height));
+        self
+    }
+
+    /// Sets the maximum size of the window in pixels.
+    pub fn with_max_size(mut self, width: u32, height: u32) -> Self {
+        self.app_config.window.max_size = Some((width, height));
+        self
+    }
+
+    /// Sets the initial position of the window on the desktop in pixels.
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.app_config.window.position = Some((x, y));
+        self
+    }
+
+    /// Sets whether the window can be resized by the user. Default is `true`.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.app_config.window.resizable = resizable;
+        self
+    }
+
+    /// Sets whether the window has a title bar and borders. Default is `true`.
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.app_config.window.decorations = decorations;
+        self
+    }
+
+    /// Makes the window start in fullscreen.
+    ///
+    /// See [Fullscreen][window::Fullscreen] for the available modes.
+    pub fn with_fullscreen(mut self, fullscreen: window::Fullscreen) -> Self {
+        self.app_config.window.fullscreen = Some(fullscreen);
+        self
+    }
+
+    /// Sets the way frames are presented. Default is [Vsync][graphics::PresentMode::Vsync].
+    pub fn with_present_mode(mut self, present_mode: graphics::PresentMode) -> Self {
+        self.app_config.window.present_mode = present_mode;
+        self
+    }
+
+    /// Makes sizes, positions, drawing and mouse coordinates use physical pixels. Default is `false`.
+    ///
+    /// By default they use logical pixels, which are scaled by the [scale factor][window::get_scale_factor]
+    /// of the monitor, so that the game looks the same size on high DPI displays.
+    pub fn with_physical_pixels(mut self, physical_pixels: bool) -> Self {
+        self.app_config.physical_pixels = physical_pixels;
+        self
+    }
+
+    /// Makes the game draw to a canvas of a fixed size, which is then scaled to the window.
+    ///
+    /// Drawing and mouse coordinates are in canvas pixels, no matter the size of the window.
+    /// This is useful for pixel art games, for example with a 320 by 180 canvas and
+    /// [PixelPerfect][graphics::ScalingMode::PixelPerfect] scaling.
+    ///
+    /// Only the [MAIN][window::MAIN] window uses the canvas. Other windows are drawn directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is 0.
+    pub fn with_virtual_resolution(
+        mut self,
+        width: u32,
+        height: u32,
+        mode: graphics::ScalingMode,
+    ) -> Self {
+        if width == 0 || height == 0 {
+            panic!("heart::graphics: the virtual resolution must be greater than 0");
+        }
+        self.renderer_config.virtual_resolution = Some(graphics::canvas::VirtualResolution {
+            width,
+            height,
+            mode,
+        });
+        self
+    }
+
+    /// Limits the number of frames drawn per second.
+    ///
+    /// Between frames and ticks, [heart][crate] sleeps instead of keeping a CPU core busy.
+    /// Without a limit, frames are drawn as fast as the [present mode][HeartBuilder::with_present_mode] allows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_fps` is 0.
+    pub fn with_max_fps(mut self, max_fps: u32) -> Self {
+        if max_fps == 0 {
+            panic!("heart::graphics: the frame rate limit must be greater than 0");
+        }
+        self.app_config.max_fps = Some(max_fps);
+        self
+    }
+
+    /// Makes the windows only be drawn after input, or after [request_redraw][graphics::request_redraw]
+    /// was called. Default is `false`.
+    ///
+    /// [Update][HeartBuilder::with_update] functions keep being called at the tick rate,
+    /// but [heart][crate] sleeps in between. This is useful for editors and tools.
+    ///
+    /// The [frame rate limit][HeartBuilder::with_max_fps] still applies, so frequent input
+    /// does not cause more frames than the limit allows.
+    pub fn with_redraw_on_demand(mut self, redraw_on_demand: bool) -> Self {
+        self.app_config.redraw_on_demand = redraw_on_demand;
+        self
+    }
+
+    /// Sets which graphics backends may be used, for example [VULKAN][wgpu::Backends::VULKAN]
+    /// or [GL][wgpu::Backends::GL]. Default is [all][wgpu::Backends::all].
+    pub fn with_backends(mut self, backends: wgpu::Backends) -> Self {
+        self.renderer_config.backends = backends;
+        self
+    }
+
+    /// Sets whether a low power or a high performance graphics adapter is preferred.
+    ///
+    /// This matters on machines with more than one GPU.
+    pub fn with_power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.renderer_config.power_preference = power_preference;
+        self
+    }
+
+    /// Forces the use of a fallback adapter, which is usually a software rasterizer.
+    ///
+    /// [run][HeartBuilder::run] fails with [NoAdapter][Error::NoAdapter] if no fallback adapter is available.
+    pub fn with_force_fallback_adapter(mut self, force_fallback_adapter: bool) -> Self {
+        self.renderer_config.force_fallback_adapter = force_fallback_adapter;
+        self
+    }
+
+    /// Requests optional features from the graphics device.
+    ///
+    /// Features that are not supported by the adapter are left out.
+    pub fn with_features(mut self, features: wgpu::Features) -> Self {
+        self.renderer_config.features = features;
+        self
+    }
+
+    /// Sets the limits required from the graphics device.
+    ///
+    /// [run][HeartBuilder::run] fails with [Device][Error::Device] if the adapter does not support them.
+    pub fn with_limits(mut self, limits: wgpu::Limits) -> Self {
+        self.renderer_config.limits = limits;
+        self
+    }
+
+    /// Runs [heart][crate] without a window, drawing to an offscreen texture of the given size instead.
+    ///
+    /// A software adapter is used if no GPU is available. Every frame runs exactly one
+    /// [update][HeartBuilder::with_update] followed by the [draw][HeartBuilder::with_draw] functions,
+    /// without waiting between frames. [heart][crate] keeps running until [close][window::close] is called.
+    ///
+    /// This is useful for automated testing on machines without a display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is 0.
+    pub fn headless(mut self, width: u32, height: u32) -> Self {
+        if width == 0 || height == 0 {
+            panic!("heart::graphics: the headless size must be greater than 0");
+        }
+        self.headless_config = Some(headless::Config { width, height });
         self
     }
 
@@ -61,6 +261,63 @@ impl HeartBuilder {
         self
     }
 
+    /// Sets the way [update][HeartBuilder::with_update] calls are scheduled when frames take longer than a tick.
+    /// Default is [Drop][time::TimestepPolicy::Drop].
+    ///
+    /// See [TimestepPolicy][time::TimestepPolicy] for the available policies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the policy is [CatchUp][time::TimestepPolicy::CatchUp] with `max_updates` of 0.
+    pub fn with_timestep_policy(mut self, timestep_policy: time::TimestepPolicy) -> Self {
+        if timestep_policy == (time::TimestepPolicy::CatchUp { max_updates: 0 }) {
+            panic!("heart::time: the maximum number of updates per frame must be greater than 0");
+        }
+        self.executor_config.timestep_policy = timestep_policy;
+        self
+    }
+
+    /// Adds another window.
+    ///
+    /// The [draw][window::Builder::with_draw] functions of the window are called once every frame
+    /// to draw it. Input functions can use [current][window::current] to find out which window an event came from.
+    /// All windows share the same [Sprite][graphics::Sprite]s and **state**.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a window with the same name has already been added.
+    pub fn with_window(mut self, window: window::Builder) -> Self {
+        let name = window.config.name;
+        if name == window::MAIN
+            || self
+                .app_config
+                .windows
+                .iter()
+                .any(|config| config.name == name)
+        {
+            panic!("heart::window: a window called `{name}` already exists");
+        }
+        self.executor_config
+            .draw
+            .entry(name)
+            .or_default()
+            .extend(window.draw);
+        self.app_config.windows.push(window.config);
+        self
+    }
+
+    /// Sets the [Scene][scene::Scene] that is on the stack when [heart][crate] starts.
+    ///
+    /// The scene is [loaded][scene::Scene::load] after the [load][HeartBuilder::with_load] functions.
+    /// See [scene] for changing scenes while [heart][crate] is running.
+    pub fn with_scene<S>(mut self, scene: S) -> Self
+    where
+        S: scene::Scene,
+    {
+        self.executor_config.scene = Some(Box::new(scene));
+        self
+    }
+
     /// Adds a function to be called once before any [update][HeartBuilder::with_update] or [draw][HeartBuilder::with_draw] calls.
     ///
     /// This should be used for one-time initialization of the game.
@@ -79,31 +336,83 @@ impl HeartBuilder {
     /// Adds a function to be called repeatedly at the frequency
     /// of the tick rate (60 times per second by default).
     ///
-    /// This should be used for the game logic.
+    /// This should be used for the game logic. The function runs in the [Update][schedule::Stage::Update] stage.
+    /// It cannot be removed. Use [schedule::add] for functions that should only run for a while.
     ///
     /// See [Update] for accepted functions.
     pub fn with_update<F, A>(mut self, mut update: F) -> Self
     where
         F: Update<A> + 'static,
     {
-        self.executor_config
-            .update
-            .push(Box::new(move |state| update.call(state)));
+        self.executor_config.stages[schedule::Stage::Update as usize].push(
+            executor::Scheduled::new(Box::new(move |state| update.call(state))),
+        );
         self
     }
 
     /// Adds a function to be will be called once every frame.
     ///
-    /// This should be used for drawing the game.
+    /// This should be used for drawing the game. The function runs in the [Draw][schedule::Stage::Draw] stage.
+    /// It cannot be removed. Use [schedule::add] for functions that should only run for a while.
     ///
     /// See [Draw] for accepted functions.
     pub fn with_draw<F, A>(mut self, mut draw: F) -> Self
     where
         F: Draw<A> + 'static,
+    {
+        self.executor_config.stages[schedule::Stage::Draw as usize].push(executor::Scheduled::new(
+            Box::new(move |state| draw.call(state)),
+        ));
+        self
+    }
+
+    /// Adds a function to be called in the given [stage][schedule::Stage].
+    ///
+    /// This is useful for functions that must run before or after all the others,
+    /// such as a debug overlay that should always be drawn last.
+    /// The function cannot be removed. Use [schedule::add] for adding functions while [heart][crate]
+    /// is running, which returns a [Handle][schedule::Handle] for [removing][schedule::remove] them.
+    ///
+    /// See [Update] for accepted functions, which are the same for every stage.
+    pub fn with_callback<F, A>(mut self, stage: schedule::Stage, mut callback: F) -> Self
+    where
+        F: Update<A> + 'static,
+    {
+        self.executor_config.stages[stage as usize].push(executor::Scheduled::new(Box::new(
+            move |state| callback.call(state),
+        )));
+        self
+    }
+
+    /// Adds a function to be called when the user tries to close the [MAIN][window::MAIN] window.
+    ///
+    /// If any of the functions return `false`, the window stays open and [heart][crate] keeps running.
+    /// This can be used to ask for confirmation first, and then call [quit] to actually stop.
+    ///
+    /// See [Quit] for accepted functions.
+    pub fn with_quit<F, A>(mut self, mut quit: F) -> Self
+    where
+        F: Quit<A> + 'static,
     {
         self.executor_config
-            .draw
-            .push(Box::new(move |state| draw.call(state)));
+            .quit
+            .push(Box::new(move |state| quit.call(state)));
+        self
+    }
+
+    /// Adds a function to be called once when [heart][crate] stops, before anything is torn down.
+    ///
+    /// This is called no matter how [heart][crate] was stopped, which makes it the place for saving data.
+    /// It is not called if [heart][crate] failed to start.
+    ///
+    /// See [Exit] for accepted functions.
+    pub fn with_exit<F, A>(mut self, mut exit: F) -> Self
+    where
+        F: Exit<A> + 'static,
+    {
+        self.executor_config
+            .exit
+            .push(Box::new(move |state| exit.call(state)));
         self
     }
 
@@ -116,9 +425,9 @@ impl HeartBuilder {
     {
         self.executor_config
             .key_pressed
-            .push(Box::new(move |state, scancode| {
-                key_pressed.call(state, scancode)
-            }));
+            .push(executor::Scheduled::new(Box::new(
+                move |state, scancode| key_pressed.call(state, scancode),
+            )));
         self
     }
 
@@ -131,9 +440,9 @@ impl HeartBuilder {
     {
         self.executor_config
             .key_released
-            .push(Box::new(move |state, scancode| {
-                key_released.call(state, scancode)
-            }));
+            .push(executor::Scheduled::new(Box::new(
+                move |state, scancode| key_released.call(state, scancode),
+            )));
         self
     }
 
@@ -146,9 +455,9 @@ impl HeartBuilder {
     {
         self.executor_config
             .mouse_pressed
-            .push(Box::new(move |state, x, y, button| {
-                mouse_pressed.call(state, x, y, button)
-            }));
+            .push(executor::Scheduled::new(Box::new(
+                move |state, x, y, button| mouse_pressed.call(state, x, y, button),
+            )));
         self
     }
 
@@ -161,14 +470,17 @@ impl HeartBuilder {
     {
         self.executor_config
             .mouse_released
-            .push(Box::new(move |state, x, y, button| {
-                mouse_released.call(state, x, y, button)
-            }));
+            .push(executor::Scheduled::new(Box::new(
+                move |state, x, y, button| mouse_released.call(state, x, y, button),
+            )));
         self
     }
 
     /// Adds a function to be called whenever the mouse is moved.
     ///
+    /// The movement is measured within one window, so it is 0 on the first call
+    /// after the mouse enters a window.
+    ///
     /// See [MouseMoved] for accepted functions.
     pub fn with_mouse_moved<F, A>(mut self, mut mouse_moved: F) -> Self
     where
@@ -176,23 +488,189 @@ impl HeartBuilder {
     {
         self.executor_config
             .mouse_moved
-            .push(Box::new(move |state, x, y, dx, dy| {
-                mouse_moved.call(state, x, y, dx, dy)
+            .push(executor::Scheduled::new(Box::new(
+                move |state, x, y, dx, dy| mouse_moved.call(state, x, y, dx, dy),
+            )));
+        self
+    }
+
+    /// Adds a function to be called when a window is resized.
+    ///
+    /// Not called when the window is minimized. The [current][window::current] window is the one that was resized.
+    ///
+    /// See [Resized] for accepted functions.
+    pub fn with_resized<F, A>(mut self, mut resized: F) -> Self
+    where
+        F: Resized<A> + 'static,
+    {
+        self.executor_config
+            .resized
+            .push(Box::new(move |state, width, height| {
+                resized.call(state, width, height)
             }));
         self
     }
 
+    /// Adds a function to be called when a window gains or loses keyboard focus.
+    ///
+    /// This can be used to pause the game when the player switches to another application.
+    ///
+    /// See [FocusChanged] for accepted functions.
+    pub fn with_focus_changed<F, A>(mut self, mut focus_changed: F) -> Self
+    where
+        F: FocusChanged<A> + 'static,
+    {
+        self.executor_config
+            .focus_changed
+            .push(Box::new(move |state, focused| {
+                focus_changed.call(state, focused)
+            }));
+        self
+    }
+
+    /// Adds a function to be called when a window becomes hidden or visible again,
+    /// for example because it is fully covered by other windows.
+    ///
+    /// Not all platforms report this.
+    ///
+    /// See [VisibilityChanged] for accepted functions.
+    pub fn with_visibility_changed<F, A>(mut self, mut visibility_changed: F) -> Self
+    where
+        F: VisibilityChanged<A> + 'static,
+    {
+        self.executor_config
+            .visibility_changed
+            .push(Box::new(move |state, visible| {
+                visibility_changed.call(state, visible)
+            }));
+        self
+    }
+
+    /// Adds a function to be called when the scale factor of a window changes,
+    /// for example because it was moved to a monitor with a different DPI.
+    ///
+    /// See [ScaleFactorChanged] for accepted functions.
+    pub fn with_scale_factor_changed<F, A>(mut self, mut scale_factor_changed: F) -> Self
+    where
+        F: ScaleFactorChanged<A> + 'static,
+    {
+        self.executor_config
+            .scale_factor_changed
+            .push(Box::new(move |state, scale_factor| {
+                scale_factor_changed.call(state, scale_factor)
+            }));
+        self
+    }
+
+    /// Adds a function to be called when the surface that the window is drawn on changes.
+    ///
+    /// This can be used to pause the game while the window is minimized.
+    ///
+    /// See [Surface] for accepted functions.
+    pub fn with_surface_event<F, A>(mut self, mut surface_event: F) -> Self
+    where
+        F: Surface<A> + 'static,
+    {
+        self.executor_config
+            .surface_event
+            .push(Box::new(move |state, event| {
+                surface_event.call(state, event)
+            }));
+        self
+    }
+
+    /// Adds a function to be called with the frame captured by [screenshot][graphics::screenshot].
+    ///
+    /// See [Screenshot] for accepted functions.
+    pub fn with_screenshot<F, A>(mut self, mut screenshot: F) -> Self
+    where
+        F: Screenshot<A> + 'static,
+    {
+        self.executor_config
+            .screenshot
+            .push(Box::new(move |state, image| screenshot.call(state, image)));
+        self
+    }
+
+    /// Adds a [Plugin], which configures the builder with its own functions and **state**.
+    pub fn with_plugin<P>(self, plugin: P) -> Self
+    where
+        P: Plugin,
+    {
+        plugin.build(self)
+    }
+
+    /// Adds a function to be called with every event of type `E` sent by [send][events::send].
+    ///
+    /// Events are delivered once per tick, before the [update][HeartBuilder::with_update] functions.
+    ///
+    /// See [Event] for accepted functions.
+    pub fn with_event<E, F, A>(mut self, mut event: F) -> Self
+    where
+        E: Send + 'static,
+        F: Event<E, A> + 'static,
+    {
+        self.executor_config
+            .events
+            .entry(std::any::TypeId::of::<E>())
+            .or_default()
+            .push(executor::Scheduled::new(Box::new(move |state, value| {
+                event.call(state, value.downcast_ref().unwrap())
+            })));
+        self
+    }
+
     /// Consumes the builder and runs [heart][crate] with the configured parameters.
-    pub fn run(self) {
-        keyboard::state::init();
-        mouse::state::init();
-        app::run(
-            self.app_config,
-            executor::Executor::new(self.executor_config),
-        );
+    ///
+    /// Returns once the window is closed, or an [Error] if [heart][crate] failed to start.
+    /// Everything is torn down before returning, so `run` can be called again to start a new session.
+    ///
+    /// The event loop is reused between sessions, so later calls must happen on the same thread
+    /// as the first one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called while a [Harness][testing::Harness] is alive on the same thread,
+    /// or from one of the functions of a running session.
+    pub fn run(self) -> Result<(), Error> {
+        let _session = session::Session::begin();
+        let executor = executor::Executor::new(self.executor_config);
+        match self.headless_config {
+            Some(headless_config) => headless::run(headless_config, self.renderer_config, executor),
+            None => app::run(self.app_config, self.renderer_config, executor),
+        }
+    }
+}
+
+impl Default for HeartBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// A reusable bundle of functions and **state**, added with [with_plugin][HeartBuilder::with_plugin].
+///
+/// ```no_run
+/// use heart::{HeartBuilder, Plugin, graphics, time};
+///
+/// struct FpsCounter;
+///
+/// impl Plugin for FpsCounter {
+///     fn build(self, builder: HeartBuilder) -> HeartBuilder {
+///         builder.with_draw(|| {
+///             graphics::set_color(1.0, 1.0, 1.0, 1.0);
+///             graphics::rectangle(0.0, 0.0, time::get_fps(), 4.0);
+///         })
+///     }
+/// }
+///
+/// heart::build().with_plugin(FpsCounter).run().unwrap();
+/// ```
+pub trait Plugin {
+    /// Adds the functions of the plugin to the builder, using its `with_*` methods.
+    fn build(self, builder: HeartBuilder) -> HeartBuilder;
+}
+
 /// A [load][HeartBuilder::with_load] function.
 ///
 /// It may optionally return a **state** value, which will later get passed back to any calls
@@ -210,14 +688,16 @@ impl<F, R> Load<R> for F where F: executor::callbacks::LoadCallback<R> {}
 
 /// An [update][HeartBuilder::with_update] function.
 ///
-/// It may optionally take a **state** argument, which must have previously been returned by a
-/// [load][HeartBuilder::with_load] call.
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
 ///
 /// Accepted function signatures:
 ///
 /// `fn()`
 ///
 /// `fn(state: &mut S)`
+///
+/// `fn(a: &mut A, b: &B, ...)`
 #[allow(private_bounds)]
 pub trait Update<A>: executor::callbacks::UpdateCallback<A> {}
 
@@ -225,31 +705,103 @@ impl<F, A> Update<A> for F where F: executor::callbacks::UpdateCallback<A> {}
 
 /// A [draw][HeartBuilder::with_draw] function.
 ///
-/// It may optionally take a **state** argument, which must have previously been returned by a
-/// [load][HeartBuilder::with_load] call.
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
 ///
 /// Accepted function signatures:
 ///
 /// `fn()`
 ///
 /// `fn(state: &mut S)`
+///
+/// `fn(a: &mut A, b: &B, ...)`
 #[allow(private_bounds)]
 pub trait Draw<A>: executor::callbacks::DrawCallback<A> {}
 
 impl<F, A> Draw<A> for F where F: executor::callbacks::DrawCallback<A> {}
 
+/// A [quit][HeartBuilder::with_quit] function.
+///
+/// Must return `true` to allow [heart][crate] to stop, or `false` to keep it running.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn() -> bool`
+///
+/// `fn(state: &mut S) -> bool`
+///
+/// `fn(a: &mut A, b: &B, ...) -> bool`
+#[allow(private_bounds)]
+pub trait Quit<A>: executor::callbacks::QuitCallback<A> {}
+
+impl<F, A> Quit<A> for F where F: executor::callbacks::QuitCallback<A> {}
+
+/// An [exit][HeartBuilder::with_exit] function.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn()`
+///
+/// `fn(state: &mut S)`
+///
+/// `fn(a: &mut A, b: &B, ...)`
+#[allow(private_bounds)]
+pub trait Exit<A>: executor::callbacks::ExitCallback<A> {}
+
+impl<F, A> Exit<A> for F where F: executor::callbacks::ExitCallback<A> {}
+
+/// Returned by input functions to stop an event from reaching the functions after them.
+///
+/// Input functions are called in the order they were added, and
+/// [Scene][scene::Scene]s receive input last. Functions returning `()` never stop an event.
+///
+/// ```no_run
+/// use heart::{EventResult, mouse::Button};
+///
+/// heart::build()
+///     .with_mouse_pressed(|x: f32, y: f32, _: Button| {
+///         if x < 100.0 && y < 40.0 {
+///             println!("button clicked");
+///             EventResult::Handled
+///         } else {
+///             EventResult::Ignored
+///         }
+///     })
+///     .with_mouse_pressed(|_: f32, _: f32, _: Button| println!("shot fired"))
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EventResult {
+    /// The event was consumed. Later functions do not receive it.
+    Handled,
+
+    /// The event is passed on to the next function.
+    Ignored,
+}
+
 /// A [key pressed][HeartBuilder::with_key_pressed] or [key released][HeartBuilder::with_key_released] function.
 ///
 /// Must take a [Scancode][keyboard::Scancode] argument.
 ///
-/// It may optionally take a **state** argument, which must have previously been returned by a
-/// [load][HeartBuilder::with_load] call.
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
 ///
 /// Accepted function signatures:
 ///
 /// `fn(scancode: Scancode)`
 ///
 /// `fn(state: &mut S, scancode: Scancode)`
+///
+/// `fn(a: &mut A, b: &B, ..., scancode: Scancode)`
+///
+/// Any of these may also return an [EventResult].
 #[allow(private_bounds)]
 pub trait Key<A>: executor::callbacks::KeyCallback<A> {}
 
@@ -259,14 +811,18 @@ impl<F, A> Key<A> for F where F: executor::callbacks::KeyCallback<A> {}
 ///
 /// Must take 2 arguments for the mouse's x and y coordinates, respectively, and a 3rd [Button][mouse::Button] argument.
 ///
-/// It may optionally take a **state** argument, which must have previously been returned by a
-/// [load][HeartBuilder::with_load] call.
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
 ///
 /// Accepted function signatures:
 ///
 /// `fn(x: f32, y: f32, button: Button)`
 ///
 /// `fn(state: &mut S, x: f32, y: f32, button: Button)`
+///
+/// `fn(a: &mut A, b: &B, ..., x: f32, y: f32, button: Button)`
+///
+/// Any of these may also return an [EventResult].
 #[allow(private_bounds)]
 pub trait Mouse<A>: executor::callbacks::MouseCallback<A> {}
 
@@ -277,15 +833,152 @@ impl<F, A> Mouse<A> for F where F: executor::callbacks::MouseCallback<A> {}
 /// Must take 2 arguments for the mouse's x and y coordinates, respectively, and 2 more arguments
 /// for the change in x and y, respectively.
 ///
-/// It may optionally take a **state** argument, which must have previously been returned by a
-/// [load][HeartBuilder::with_load] call.
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
 ///
 /// Accepted function signatures:
 ///
 /// `fn(x: f32, y: f32, dx: f32, dy: f32)`
 ///
 /// `fn(state: &mut S, x: f32, y: f32, dx: f32, dy: f32)`
+///
+/// `fn(a: &mut A, b: &B, ..., x: f32, y: f32, dx: f32, dy: f32)`
+///
+/// Any of these may also return an [EventResult].
 #[allow(private_bounds)]
 pub trait MouseMoved<A>: executor::callbacks::MouseMovedCallback<A> {}
 
 impl<F, A> MouseMoved<A> for F where F: executor::callbacks::MouseMovedCallback<A> {}
+
+/// A [resized][HeartBuilder::with_resized] function.
+///
+/// Must take 2 arguments for the new width and height of the window in pixels, respectively.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(width: u32, height: u32)`
+///
+/// `fn(state: &mut S, width: u32, height: u32)`
+///
+/// `fn(a: &mut A, b: &B, ..., width: u32, height: u32)`
+#[allow(private_bounds)]
+pub trait Resized<A>: executor::callbacks::ResizedCallback<A> {}
+
+impl<F, A> Resized<A> for F where F: executor::callbacks::ResizedCallback<A> {}
+
+/// A [focus changed][HeartBuilder::with_focus_changed] function.
+///
+/// Must take a `bool` argument, which is `true` if the window gained focus.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(focused: bool)`
+///
+/// `fn(state: &mut S, focused: bool)`
+///
+/// `fn(a: &mut A, b: &B, ..., focused: bool)`
+#[allow(private_bounds)]
+pub trait FocusChanged<A>: executor::callbacks::FocusChangedCallback<A> {}
+
+impl<F, A> FocusChanged<A> for F where F: executor::callbacks::FocusChangedCallback<A> {}
+
+/// A [visibility changed][HeartBuilder::with_visibility_changed] function.
+///
+/// Must take a `bool` argument, which is `true` if the window became visible.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(visible: bool)`
+///
+/// `fn(state: &mut S, visible: bool)`
+///
+/// `fn(a: &mut A, b: &B, ..., visible: bool)`
+#[allow(private_bounds)]
+pub trait VisibilityChanged<A>: executor::callbacks::VisibilityChangedCallback<A> {}
+
+impl<F, A> VisibilityChanged<A> for F where F: executor::callbacks::VisibilityChangedCallback<A> {}
+
+/// A [scale factor changed][HeartBuilder::with_scale_factor_changed] function.
+///
+/// Must take an `f64` argument for the new scale factor.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(scale_factor: f64)`
+///
+/// `fn(state: &mut S, scale_factor: f64)`
+///
+/// `fn(a: &mut A, b: &B, ..., scale_factor: f64)`
+#[allow(private_bounds)]
+pub trait ScaleFactorChanged<A>: executor::callbacks::ScaleFactorChangedCallback<A> {}
+
+impl<F, A> ScaleFactorChanged<A> for F where F: executor::callbacks::ScaleFactorChangedCallback<A> {}
+
+/// A [surface event][HeartBuilder::with_surface_event] function.
+///
+/// Must take a [SurfaceEvent][graphics::SurfaceEvent] argument.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(event: SurfaceEvent)`
+///
+/// `fn(state: &mut S, event: SurfaceEvent)`
+///
+/// `fn(a: &mut A, b: &B, ..., event: SurfaceEvent)`
+#[allow(private_bounds)]
+pub trait Surface<A>: executor::callbacks::SurfaceCallback<A> {}
+
+impl<F, A> Surface<A> for F where F: executor::callbacks::SurfaceCallback<A> {}
+
+/// A [screenshot][HeartBuilder::with_screenshot] function.
+///
+/// Must take an [Image][image::Image] argument.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(image: &Image)`
+///
+/// `fn(state: &mut S, image: &Image)`
+///
+/// `fn(a: &mut A, b: &B, ..., image: &Image)`
+#[allow(private_bounds)]
+pub trait Screenshot<A>: executor::callbacks::ScreenshotCallback<A> {}
+
+impl<F, A> Screenshot<A> for F where F: executor::callbacks::ScreenshotCallback<A> {}
+
+/// An [event][HeartBuilder::with_event] function.
+///
+/// Must take a reference to the event of type `E`.
+///
+/// It may optionally take up to four **state** arguments of distinct types, each either as
+/// `&mut S` or `&S`, which must have previously been returned by [load][HeartBuilder::with_load] calls.
+///
+/// Accepted function signatures:
+///
+/// `fn(event: &E)`
+///
+/// `fn(state: &mut S, event: &E)`
+///
+/// `fn(a: &mut A, b: &B, ..., event: &E)`
+#[allow(private_bounds)]
+pub trait Event<E, A>: executor::callbacks::EventCallback<E, A> {}
+
+impl<F, E, A> Event<E, A> for F where F: executor::callbacks::EventCallback<E, A> {}
@@ -20,7 +20,18 @@ pub fn is_pressed(button: Button) -> bool {
     state::get_button(button)
 }
 
-/// Get the x and y coordinates of the mouse.
+/// Check if the mouse is captured by an input function, for example because it is over a UI element.
+///
+/// The mouse is captured while the latest [mouse moved][crate::HeartBuilder::with_mouse_moved] event
+/// was [handled][crate::EventResult::Handled], and while a button whose
+/// [mouse pressed][crate::HeartBuilder::with_mouse_pressed] event was handled is held down.
+/// Leaving the window ends the capture by mouse moved events, and losing focus ends the capture by buttons.
+/// Update functions can use this to ignore mouse input that belongs to the UI.
+pub fn is_captured() -> bool {
+    state::is_captured()
+}
+
+/// Get the x and y coordinates of the mouse, relative to the window it was last moved over.
 pub fn get_position() -> (f32, f32) {
     state::get_position()
 }
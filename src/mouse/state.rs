@@ -1,44 +1,69 @@
-use std::sync::{
-    Arc, OnceLock,
-    atomic::{AtomicBool, AtomicU32, Ordering},
-};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use super::Button;
 
 struct State {
     buttons: [AtomicBool; 3],
+    captured: [AtomicBool; 3],
+    hovered: AtomicBool,
     x: AtomicU32,
     y: AtomicU32,
 }
 
-static STATE: OnceLock<Arc<State>> = OnceLock::new();
+static STATE: State = State {
+    buttons: [const { AtomicBool::new(false) }; 3],
+    captured: [const { AtomicBool::new(false) }; 3],
+    hovered: AtomicBool::new(false),
+    x: AtomicU32::new(f32::NAN.to_bits()),
+    y: AtomicU32::new(f32::NAN.to_bits()),
+};
 
 pub(crate) fn init() {
-    let _ = STATE.set(Arc::new(State {
-        buttons: [const { AtomicBool::new(false) }; 3],
-        x: AtomicU32::new(f32::NAN.to_bits()),
-        y: AtomicU32::new(f32::NAN.to_bits()),
-    }));
+    for button in STATE.buttons.iter().chain(&STATE.captured) {
+        button.store(false, Ordering::Relaxed);
+    }
+    STATE.hovered.store(false, Ordering::Relaxed);
+    set_position(f32::NAN, f32::NAN);
 }
 
 pub(crate) fn get_button(button: Button) -> bool {
-    STATE.get().unwrap().buttons[button as usize].load(Ordering::Relaxed)
+    STATE.buttons[button as usize].load(Ordering::Relaxed)
 }
 
 pub(crate) fn set_button(button: Button, pressed: bool) {
-    STATE.get().unwrap().buttons[button as usize].store(pressed, Ordering::Relaxed);
+    STATE.buttons[button as usize].store(pressed, Ordering::Relaxed);
+}
+
+pub(crate) fn set_captured(button: Button, captured: bool) {
+    STATE.captured[button as usize].store(captured, Ordering::Relaxed);
+}
+
+pub(crate) fn release_captured() {
+    for captured in &STATE.captured {
+        captured.store(false, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn set_hovered(hovered: bool) {
+    STATE.hovered.store(hovered, Ordering::Relaxed);
+}
+
+pub(crate) fn is_captured() -> bool {
+    STATE.hovered.load(Ordering::Relaxed)
+        || STATE
+            .captured
+            .iter()
+            .any(|captured| captured.load(Ordering::Relaxed))
 }
 
 pub(crate) fn get_position() -> (f32, f32) {
-    let state = STATE.get().unwrap();
     (
-        f32::from_bits(state.x.load(Ordering::Relaxed)),
-        f32::from_bits(state.y.load(Ordering::Relaxed)),
+        f32::from_bits(STATE.x.load(Ordering::Relaxed)),
+        f32::from_bits(STATE.y.load(Ordering::Relaxed)),
     )
 }
 
 pub(crate) fn set_position(x: f32, y: f32) {
-    let state = STATE.get().unwrap();
-    state.x.store(x.to_bits(), Ordering::Relaxed);
-    state.y.store(y.to_bits(), Ordering::Relaxed);
+    STATE.x.store(x.to_bits(), Ordering::Relaxed);
+    STATE.y.store(y.to_bits(), Ordering::Relaxed);
 }
@@ -0,0 +1,166 @@
+//! Scenes.
+//!
+//! A [Scene] is one part of the game with its own update, draw and input functions,
+//! such as a title screen, the gameplay or a pause menu. Scenes are kept on a stack,
+//! and only the scene on top receives updates and input. Scenes can be changed from
+//! any callback with [push], [pop] and [switch], optionally with a [Transition].
+//!
+//! Changes are queued and applied before the next [update][crate::HeartBuilder::with_update],
+//! so the scene that requested a change keeps running until the current callback returns.
+//! Scenes do not need to be [Send], so they can only be changed from the thread that runs [heart][crate].
+//!
+//! The functions added to the [HeartBuilder][crate::HeartBuilder] keep running for every scene.
+//! They are called before the functions of the scenes.
+//!
+//! ```no_run
+//! use heart::{graphics, keyboard::Scancode, scene::{self, Scene}};
+//!
+//! struct Title;
+//!
+//! impl Scene for Title {
+//!     fn key_pressed(&mut self, _: Scancode) {
+//!         scene::switch_with(Game, scene::Transition::Fade(std::time::Duration::from_secs(1)));
+//!     }
+//! }
+//!
+//! struct Game;
+//!
+//! impl Scene for Game {
+//!     fn draw(&mut self) {
+//!         graphics::rectangle(10.0, 10.0, 100.0, 100.0);
+//!     }
+//! }
+//!
+//! heart::build().with_scene(Title).run().unwrap();
+//! ```
+//!
+//! See also:  
+//! [with scene][crate::HeartBuilder::with_scene]  
+
+use std::time::Duration;
+
+use crate::{keyboard, mouse};
+
+pub(crate) mod state;
+
+/// A part of the game with its own functions.
+///
+/// All functions do nothing by default.
+///
+/// Unlike the functions added to the [HeartBuilder][crate::HeartBuilder], the functions of a scene
+/// do not receive the values returned by [load][crate::HeartBuilder::with_load] functions.
+/// Keep the data a scene needs in the scene itself, for example by passing it to the scene when it is created.
+#[allow(unused_variables)]
+pub trait Scene: 'static {
+    /// Called once when the scene is added to the stack.
+    fn load(&mut self) {}
+
+    /// Called once when the scene is removed from the stack, or when [heart][crate] stops.
+    fn unload(&mut self) {}
+
+    /// Called when another scene is pushed on top of this one.
+    fn pause(&mut self) {}
+
+    /// Called when this scene is on top of the stack again, after the scene above it was popped.
+    fn resume(&mut self) {}
+
+    /// Called at the frequency of the tick rate while the scene is on top of the stack.
+    fn update(&mut self) {}
+
+    /// Called once every frame to draw the [MAIN][crate::window::MAIN] window while the scene is visible.
+    fn draw(&mut self) {}
+
+    /// Called on keyboard key press while the scene is on top of the stack.
+    fn key_pressed(&mut self, scancode: keyboard::Scancode) {}
+
+    /// Called on keyboard key release while the scene is on top of the stack.
+    fn key_released(&mut self, scancode: keyboard::Scancode) {}
+
+    /// Called on mouse button press while the scene is on top of the stack.
+    fn mouse_pressed(&mut self, x: f32, y: f32, button: mouse::Button) {}
+
+    /// Called on mouse button release while the scene is on top of the stack.
+    fn mouse_released(&mut self, x: f32, y: f32, button: mouse::Button) {}
+
+    /// Called whenever the mouse is moved while the scene is on top of the stack.
+    fn mouse_moved(&mut self, x: f32, y: f32, dx: f32, dy: f32) {}
+
+    /// Returns whether the scene below this one is drawn first. Default is `false`.
+    ///
+    /// This is useful for pause menus that are drawn over the paused game.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// Represents the way a change of scenes looks.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// Changes the scene immediately.
+    Cut,
+
+    /// Fades to black over half of the duration, changes the scene, and fades back in over the other half.
+    ///
+    /// The top scene keeps being updated while fading, but it does not receive input.
+    /// Further changes are applied after the fade has finished.
+    ///
+    /// The fade is only drawn over the [MAIN][crate::window::MAIN] window, where the scenes are drawn.
+    Fade(Duration),
+}
+
+/// Pushes a scene on top of the stack.
+///
+/// The previous top scene is [paused][Scene::pause] and stays on the stack.
+pub fn push<S>(scene: S)
+where
+    S: Scene,
+{
+    push_with(scene, Transition::Cut);
+}
+
+/// Pushes a scene on top of the stack using the given transition.
+///
+/// See [push].
+pub fn push_with<S>(scene: S, transition: Transition)
+where
+    S: Scene,
+{
+    state::queue(state::Command::Push(Box::new(scene)), transition);
+}
+
+/// Removes the top scene from the stack.
+///
+/// The scene below it is [resumed][Scene::resume]. Does nothing if the stack is empty.
+pub fn pop() {
+    pop_with(Transition::Cut);
+}
+
+/// Removes the top scene from the stack using the given transition.
+///
+/// See [pop].
+pub fn pop_with(transition: Transition) {
+    state::queue(state::Command::Pop, transition);
+}
+
+/// Replaces the top scene of the stack, or pushes the scene if the stack is empty.
+pub fn switch<S>(scene: S)
+where
+    S: Scene,
+{
+    switch_with(scene, Transition::Cut);
+}
+
+/// Replaces the top scene of the stack using the given transition.
+///
+/// See [switch].
+pub fn switch_with<S>(scene: S, transition: Transition)
+where
+    S: Scene,
+{
+    state::queue(state::Command::Switch(Box::new(scene)), transition);
+}
+
+/// Get the number of scenes on the stack.
+pub fn get_depth() -> usize {
+    state::get_depth()
+}
@@ -0,0 +1,166 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    time::Duration,
+};
+
+use crate::{graphics, session};
+
+use super::{Scene, Transition};
+
+pub(crate) enum Command {
+    Push(Box<dyn Scene>),
+    Pop,
+    Switch(Box<dyn Scene>),
+}
+
+thread_local! {
+    static COMMANDS: RefCell<VecDeque<(Command, Transition)>> = const { RefCell::new(VecDeque::new()) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+pub(crate) fn init() {
+    COMMANDS.take();
+    DEPTH.set(0);
+}
+
+pub(crate) fn queue(command: Command, transition: Transition) {
+    debug_assert!(
+        session::is_active(),
+        "heart::scene: scenes can only be changed from the thread that runs heart"
+    );
+    COMMANDS.with_borrow_mut(|commands| commands.push_back((command, transition)));
+}
+
+fn take() -> Option<(Command, Transition)> {
+    COMMANDS.with_borrow_mut(VecDeque::pop_front)
+}
+
+pub(crate) fn get_depth() -> usize {
+    DEPTH.get()
+}
+
+struct Fade {
+    command: Option<Command>,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl Fade {
+    fn alpha(&self) -> f32 {
+        let progress = self.elapsed.as_secs_f32() / self.duration.as_secs_f32().max(f32::EPSILON);
+        (1.0 - (progress * 2.0 - 1.0).abs()).clamp(0.0, 1.0)
+    }
+}
+
+pub(crate) struct Stack {
+    scenes: Vec<Box<dyn Scene>>,
+    fade: Option<Fade>,
+}
+
+impl Stack {
+    pub(crate) fn new() -> Self {
+        Self {
+            scenes: Vec::new(),
+            fade: None,
+        }
+    }
+
+    pub(crate) fn apply(&mut self, command: Command) {
+        match command {
+            Command::Push(mut scene) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    top.pause();
+                }
+                scene.load();
+                self.scenes.push(scene);
+            }
+            Command::Pop => {
+                if let Some(mut scene) = self.scenes.pop() {
+                    scene.unload();
+                    if let Some(top) = self.scenes.last_mut() {
+                        top.resume();
+                    }
+                }
+            }
+            Command::Switch(mut scene) => {
+                if let Some(mut top) = self.scenes.pop() {
+                    top.unload();
+                }
+                scene.load();
+                self.scenes.push(scene);
+            }
+        }
+        DEPTH.set(self.scenes.len());
+    }
+
+    pub(crate) fn process(&mut self) {
+        while self.fade.is_none()
+            && let Some((command, transition)) = take()
+        {
+            match transition {
+                Transition::Cut => self.apply(command),
+                Transition::Fade(duration) => {
+                    self.fade = Some(Fade {
+                        command: Some(command),
+                        duration,
+                        elapsed: Duration::ZERO,
+                    })
+                }
+            }
+        }
+    }
+
+    pub(crate) fn update(&mut self, delta: Duration) {
+        self.process();
+        if let Some(fade) = &mut self.fade {
+            fade.elapsed += delta;
+            let command = (fade.elapsed >= fade.duration / 2)
+                .then(|| fade.command.take())
+                .flatten();
+            if fade.elapsed >= fade.duration {
+                self.fade = None;
+            }
+            if let Some(command) = command {
+                self.apply(command);
+            }
+        }
+        if let Some(top) = self.scenes.last_mut() {
+            top.update();
+        }
+    }
+
+    pub(crate) fn draw(&mut self) {
+        let bottom = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        self.scenes[bottom..]
+            .iter_mut()
+            .for_each(|scene| scene.draw());
+        if let Some(fade) = &self.fade {
+            graphics::context::cover(graphics::renderer::Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: fade.alpha(),
+            });
+        }
+    }
+
+    pub(crate) fn top(&mut self) -> Option<&mut dyn Scene> {
+        match self.fade {
+            Some(_) => None,
+            None => Some(self.scenes.last_mut()?.as_mut()),
+        }
+    }
+
+    pub(crate) fn unload(&mut self) {
+        while let Some(mut scene) = self.scenes.pop() {
+            scene.unload();
+        }
+        self.fade = None;
+        DEPTH.set(0);
+    }
+}
@@ -0,0 +1,182 @@
+//! Ordering of callbacks.
+//!
+//! Every tick, the [update][crate::HeartBuilder::with_update] functions run in the
+//! [PreUpdate][Stage::PreUpdate], [Update][Stage::Update] and [PostUpdate][Stage::PostUpdate] stages.
+//! Every frame, the [MAIN][crate::window::MAIN] window is drawn by the functions in the
+//! [PreDraw][Stage::PreDraw], [Draw][Stage::Draw] and [Overlay][Stage::Overlay] stages.
+//! Within a stage, functions run in the order they were added.
+//!
+//! Functions can also be added and removed while [heart][crate] is running, for example
+//! for a cutscene that only runs for a while and skips ahead when a key is pressed.
+//! Besides stages, functions can be added for keyboard and mouse input and for [events][crate::events].
+//! Changes are applied before the next stage starts or the next input or event is delivered.
+//! Only functions added here can be removed. The functions added to the
+//! [HeartBuilder][crate::HeartBuilder] keep running for as long as [heart][crate] does.
+//!
+//! Adding and removing functions is only possible from the thread that runs [heart][crate],
+//! which is where all of its functions are called.
+//!
+//! ```no_run
+//! use heart::{graphics, keyboard::Scancode, schedule::{self, Stage}};
+//!
+//! fn draw_debug() {
+//!     graphics::set_color(1.0, 0.0, 0.0, 1.0);
+//!     graphics::rectangle(0.0, 0.0, 4.0, 4.0);
+//! }
+//!
+//! heart::build()
+//!     .with_callback(Stage::Overlay, draw_debug)
+//!     .with_load(|| {
+//!         let cutscene = schedule::add(Stage::Update, || println!("cutscene"));
+//!         schedule::add_key_pressed(move |scancode| {
+//!             if scancode == Scancode::Escape {
+//!                 schedule::remove(cutscene);
+//!             }
+//!         });
+//!     })
+//!     .run()
+//!     .unwrap();
+//! ```
+//!
+//! See also:  
+//! [with callback][crate::HeartBuilder::with_callback]  
+
+use std::any::TypeId;
+
+use crate::{Event, Key, Mouse, MouseMoved, Update};
+
+pub(crate) mod state;
+
+/// Represents the point in a tick or frame at which a function runs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Runs at the start of every tick, for example for reading input.
+    PreUpdate,
+
+    /// Runs every tick, after [PreUpdate][Stage::PreUpdate].
+    /// This is where [with_update][crate::HeartBuilder::with_update] adds functions.
+    ///
+    /// The top [Scene][crate::scene::Scene] is updated at the end of this stage.
+    Update,
+
+    /// Runs at the end of every tick, for example for collision checks or cleanup.
+    PostUpdate,
+
+    /// Runs at the start of every frame, for example for clearing the screen.
+    PreDraw,
+
+    /// Runs every frame, after [PreDraw][Stage::PreDraw].
+    /// This is where [with_draw][crate::HeartBuilder::with_draw] adds functions.
+    ///
+    /// The visible [Scene][crate::scene::Scene]s are drawn at the end of this stage.
+    Draw,
+
+    /// Runs at the end of every frame, on top of everything else including
+    /// [scene transitions][crate::scene::Transition]. Useful for debug overlays.
+    Overlay,
+}
+
+/// A handle to a function added with [add] or one of the other functions in this module,
+/// which can be passed to [remove].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Handle(u64);
+
+/// Adds a function to run in the given stage, after the functions already in it.
+///
+/// The function is added before the next stage starts, so adding to the current stage
+/// takes effect on the next tick or frame.
+///
+/// See [Update] for accepted functions, which are the same for every stage.
+pub fn add<F, A>(stage: Stage, mut callback: F) -> Handle
+where
+    F: Update<A> + 'static,
+{
+    state::add(state::Registration::Stage(
+        stage,
+        Box::new(move |state| callback.call(state)),
+    ))
+}
+
+/// Adds a function to be called on keyboard key press, after the functions already added.
+///
+/// See [with_key_pressed][crate::HeartBuilder::with_key_pressed] and [Key] for accepted functions.
+pub fn add_key_pressed<F, A>(mut key_pressed: F) -> Handle
+where
+    F: Key<A> + 'static,
+{
+    state::add(state::Registration::KeyPressed(Box::new(
+        move |state, scancode| key_pressed.call(state, scancode),
+    )))
+}
+
+/// Adds a function to be called on keyboard key release, after the functions already added.
+///
+/// See [with_key_released][crate::HeartBuilder::with_key_released] and [Key] for accepted functions.
+pub fn add_key_released<F, A>(mut key_released: F) -> Handle
+where
+    F: Key<A> + 'static,
+{
+    state::add(state::Registration::KeyReleased(Box::new(
+        move |state, scancode| key_released.call(state, scancode),
+    )))
+}
+
+/// Adds a function to be called on mouse button press, after the functions already added.
+///
+/// See [with_mouse_pressed][crate::HeartBuilder::with_mouse_pressed] and [Mouse] for accepted functions.
+pub fn add_mouse_pressed<F, A>(mut mouse_pressed: F) -> Handle
+where
+    F: Mouse<A> + 'static,
+{
+    state::add(state::Registration::MousePressed(Box::new(
+        move |state, x, y, button| mouse_pressed.call(state, x, y, button),
+    )))
+}
+
+/// Adds a function to be called on mouse button release, after the functions already added.
+///
+/// See [with_mouse_released][crate::HeartBuilder::with_mouse_released] and [Mouse] for accepted functions.
+pub fn add_mouse_released<F, A>(mut mouse_released: F) -> Handle
+where
+    F: Mouse<A> + 'static,
+{
+    state::add(state::Registration::MouseReleased(Box::new(
+        move |state, x, y, button| mouse_released.call(state, x, y, button),
+    )))
+}
+
+/// Adds a function to be called whenever the mouse is moved, after the functions already added.
+///
+/// See [with_mouse_moved][crate::HeartBuilder::with_mouse_moved] and [MouseMoved] for accepted functions.
+pub fn add_mouse_moved<F, A>(mut mouse_moved: F) -> Handle
+where
+    F: MouseMoved<A> + 'static,
+{
+    state::add(state::Registration::MouseMoved(Box::new(
+        move |state, x, y, dx, dy| mouse_moved.call(state, x, y, dx, dy),
+    )))
+}
+
+/// Adds a function to be called with every event of type `E`, after the functions already added.
+///
+/// The function receives the events delivered from the next tick on.
+///
+/// See [with_event][crate::HeartBuilder::with_event] and [Event] for accepted functions.
+pub fn add_event<E, F, A>(mut event: F) -> Handle
+where
+    E: Send + 'static,
+    F: Event<E, A> + 'static,
+{
+    state::add(state::Registration::Event(
+        TypeId::of::<E>(),
+        Box::new(move |state, value| event.call(state, value.downcast_ref().unwrap())),
+    ))
+}
+
+/// Removes a function previously added with any of the functions in this module.
+///
+/// The function is removed before the next stage starts or the next input or event is
+/// delivered. Removing a function that has already been removed does nothing.
+pub fn remove(handle: Handle) {
+    state::queue(state::Command::Remove(handle));
+}
@@ -0,0 +1,103 @@
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+};
+
+use crate::{
+    executor::{
+        Callback, Config, EventCallback, KeyCallback, MouseCallback, MouseMovedCallback, Scheduled,
+    },
+    session,
+};
+
+use super::{Handle, Stage};
+
+pub(crate) enum Registration {
+    Stage(Stage, Callback),
+    KeyPressed(KeyCallback),
+    KeyReleased(KeyCallback),
+    MousePressed(MouseCallback),
+    MouseReleased(MouseCallback),
+    MouseMoved(MouseMovedCallback),
+    Event(TypeId, EventCallback),
+}
+
+pub(crate) enum Command {
+    Add(Handle, Registration),
+    Remove(Handle),
+}
+
+thread_local! {
+    static COMMANDS: RefCell<Vec<Command>> = const { RefCell::new(Vec::new()) };
+    static NEXT_HANDLE: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn init() {
+    COMMANDS.take();
+}
+
+pub(crate) fn add(registration: Registration) -> Handle {
+    let handle = Handle(NEXT_HANDLE.get());
+    NEXT_HANDLE.set(handle.0 + 1);
+    queue(Command::Add(handle, registration));
+    handle
+}
+
+pub(crate) fn queue(command: Command) {
+    debug_assert!(
+        session::is_active(),
+        "heart::schedule: functions can only be added and removed on the thread that runs heart"
+    );
+    COMMANDS.with_borrow_mut(|commands| commands.push(command));
+}
+
+pub(crate) fn apply(config: &mut Config) {
+    for command in COMMANDS.take() {
+        match command {
+            Command::Add(handle, registration) => match registration {
+                Registration::Stage(stage, callback) => {
+                    config.stages[stage as usize].push(Scheduled::with_handle(handle, callback))
+                }
+                Registration::KeyPressed(callback) => config
+                    .key_pressed
+                    .push(Scheduled::with_handle(handle, callback)),
+                Registration::KeyReleased(callback) => config
+                    .key_released
+                    .push(Scheduled::with_handle(handle, callback)),
+                Registration::MousePressed(callback) => config
+                    .mouse_pressed
+                    .push(Scheduled::with_handle(handle, callback)),
+                Registration::MouseReleased(callback) => config
+                    .mouse_released
+                    .push(Scheduled::with_handle(handle, callback)),
+                Registration::MouseMoved(callback) => config
+                    .mouse_moved
+                    .push(Scheduled::with_handle(handle, callback)),
+                Registration::Event(type_id, callback) => config
+                    .events
+                    .entry(type_id)
+                    .or_default()
+                    .push(Scheduled::with_handle(handle, callback)),
+            },
+            Command::Remove(handle) => {
+                config
+                    .stages
+                    .iter_mut()
+                    .for_each(|stage| remove(stage, handle));
+                remove(&mut config.key_pressed, handle);
+                remove(&mut config.key_released, handle);
+                remove(&mut config.mouse_pressed, handle);
+                remove(&mut config.mouse_released, handle);
+                remove(&mut config.mouse_moved, handle);
+                config
+                    .events
+                    .values_mut()
+                    .for_each(|events| remove(events, handle));
+            }
+        }
+    }
+}
+
+fn remove<C>(scheduled: &mut Vec<Scheduled<C>>, handle: Handle) {
+    scheduled.retain(|scheduled| scheduled.handle != Some(handle));
+}
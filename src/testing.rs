@@ -0,0 +1,180 @@
+//! Deterministic testing.
+//!
+//! A [Harness] runs the functions added to a [HeartBuilder] without a window, without
+//! real time and without real input. Ticks only happen when [step][Harness::step] is called,
+//! and input only happens when it is injected through the harness. This makes it possible
+//! to test game logic with `#[test]` functions.
+//!
+//! Drawing happens on an offscreen texture, the same way as in
+//! [headless][HeartBuilder::headless] mode. The renderer is only created once it is needed,
+//! by the first [draw][Harness::draw] or graphics function such as
+//! [create_sprite][crate::graphics::create_sprite], so tests that never draw also run on
+//! machines without a graphics adapter.
+//!
+//! ```no_run
+//! use heart::{keyboard::Scancode, testing::Harness};
+//!
+//! struct Player {
+//!     y: f32,
+//!     velocity: f32,
+//! }
+//!
+//! impl Player {
+//!     fn new() -> Self {
+//!         Self { y: 0.0, velocity: 0.0 }
+//!     }
+//!
+//!     fn update(&mut self) {
+//!         self.y += self.velocity;
+//!         self.velocity += 1.0;
+//!         if self.y > 0.0 {
+//!             self.y = 0.0;
+//!             self.velocity = 0.0;
+//!         }
+//!     }
+//!
+//!     fn key_pressed(&mut self, scancode: Scancode) {
+//!         if scancode == Scancode::Space {
+//!             self.velocity = -10.0;
+//!         }
+//!     }
+//! }
+//!
+//! let mut harness = Harness::new(
+//!     heart::build()
+//!         .with_load(Player::new)
+//!         .with_update(Player::update)
+//!         .with_key_pressed(Player::key_pressed),
+//! );
+//!
+//! harness.key_pressed(Scancode::Space);
+//! harness.step(5);
+//! assert!(harness.state::<Player>().unwrap().y < 0.0);
+//! ```
+
+use crate::{Error, HeartBuilder, executor, graphics, headless, keyboard, mouse, session, window};
+
+/// Drives the functions added to a [HeartBuilder] step by step.
+///
+/// Dropping the harness calls the [exit][HeartBuilder::with_exit] functions.
+///
+/// Only one harness, or one [run][HeartBuilder::run], can be active at a time. Creating
+/// a harness on another thread blocks until the first one is dropped, so harnesses are safe
+/// to use from tests that run in parallel. Within one test, drop a harness before creating the next.
+pub struct Harness {
+    executor: executor::Executor,
+    headless_config: headless::Config,
+    headless: Option<headless::Headless>,
+    _session: session::Session,
+}
+
+impl Harness {
+    /// Creates a harness from a builder and calls its [load][HeartBuilder::with_load] functions.
+    ///
+    /// The offscreen texture has the size set by [headless][HeartBuilder::headless] or
+    /// [with_size][HeartBuilder::with_size], or 800 by 600 pixels otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another harness is alive on the same thread.
+    pub fn new(builder: HeartBuilder) -> Self {
+        let session = session::Session::begin();
+        let headless_config = builder.headless_config.unwrap_or_else(|| {
+            let (width, height) = builder.app_config.window.size.unwrap_or((800, 600));
+            headless::Config { width, height }
+        });
+        let renderer_config = builder.renderer_config;
+        graphics::context::init_lazily(Box::new(move || {
+            headless::Headless::create_renderer(&renderer_config)
+        }));
+        window::state::init_headless(headless_config.width, headless_config.height);
+        let mut executor = executor::Executor::new(builder.executor_config);
+        executor.load();
+        Self {
+            executor,
+            headless_config,
+            headless: None,
+            _session: session,
+        }
+    }
+
+    /// Runs the [update][HeartBuilder::with_update] functions `ticks` times.
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.executor.step();
+        }
+    }
+
+    /// Runs the [draw][HeartBuilder::with_draw] functions and renders the frame.
+    ///
+    /// # Errors
+    ///
+    /// Creates the renderer if it does not exist yet. Returns [NoAdapter][Error::NoAdapter]
+    /// if there is neither a GPU nor a software fallback adapter, or another [Error] if the
+    /// graphics device could not be created.
+    pub fn draw(&mut self) -> Result<(), Error> {
+        let headless = match self.headless.take() {
+            Some(headless) => headless,
+            None => {
+                graphics::context::ensure()?;
+                headless::Headless::new(&self.headless_config)
+            }
+        };
+        headless.draw(&mut self.executor);
+        self.headless = Some(headless);
+        Ok(())
+    }
+
+    /// Presses a key, calling the [key pressed][HeartBuilder::with_key_pressed] functions.
+    pub fn key_pressed(&mut self, scancode: keyboard::Scancode) {
+        keyboard::state::set_key(scancode, true);
+        self.executor.key_pressed(scancode);
+    }
+
+    /// Releases a key, calling the [key released][HeartBuilder::with_key_released] functions.
+    pub fn key_released(&mut self, scancode: keyboard::Scancode) {
+        keyboard::state::set_key(scancode, false);
+        self.executor.key_released(scancode);
+    }
+
+    /// Moves the mouse to `x` and `y`, calling the [mouse moved][HeartBuilder::with_mouse_moved] functions.
+    pub fn mouse_moved(&mut self, x: f32, y: f32) {
+        let (x_0, y_0) = mouse::state::get_position();
+        let (dx, dy) = match x_0.is_nan() {
+            true => (0.0, 0.0),
+            false => (x - x_0, y - y_0),
+        };
+        mouse::state::set_position(x, y);
+        self.executor.mouse_moved(x, y, dx, dy);
+    }
+
+    /// Presses a mouse button at the current mouse position,
+    /// calling the [mouse pressed][HeartBuilder::with_mouse_pressed] functions.
+    pub fn mouse_pressed(&mut self, button: mouse::Button) {
+        let (x, y) = mouse::state::get_position();
+        mouse::state::set_button(button, true);
+        self.executor.mouse_pressed(x, y, button);
+    }
+
+    /// Releases a mouse button at the current mouse position,
+    /// calling the [mouse released][HeartBuilder::with_mouse_released] functions.
+    pub fn mouse_released(&mut self, button: mouse::Button) {
+        let (x, y) = mouse::state::get_position();
+        mouse::state::set_button(button, false);
+        self.executor.mouse_released(x, y, button);
+    }
+
+    /// Get the **state** of type `S` previously returned by a [load][HeartBuilder::with_load] call.
+    pub fn state<S>(&mut self) -> Option<&mut S>
+    where
+        S: 'static,
+    {
+        self.executor.state().retrieve()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.executor.exit();
+    }
+}
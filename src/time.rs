@@ -0,0 +1,93 @@
+//! Timing information.
+//!
+//! Contains functions for querying how much time has passed while
+//! [heart][crate] is running, and how fast it is running.
+//!
+//! See also:  
+//! [with tick rate][crate::HeartBuilder::with_tick_rate]  
+//! [with timestep policy][crate::HeartBuilder::with_timestep_policy]  
+
+use std::time::Duration;
+
+pub(crate) mod state;
+
+/// Represents the way [update][crate::HeartBuilder::with_update] calls are scheduled when frames take
+/// longer than a tick.
+///
+/// See [with timestep policy][crate::HeartBuilder::with_timestep_policy].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TimestepPolicy {
+    /// Runs at most one update per frame. If the game falls more than a tick behind,
+    /// the extra time is dropped, so the game runs in slow motion instead of catching up.
+    Drop,
+
+    /// Runs as many updates per frame as needed to catch up with real time, but at most
+    /// `max_updates`. Time beyond that is dropped, so slow frames cannot make the game fall further and further behind.
+    CatchUp {
+        /// The maximum number of updates per frame. Must be greater than 0.
+        max_updates: u32,
+    },
+
+    /// Runs one update per frame, which simulates the real time since the previous one,
+    /// but at most `max_delta`. Use [get_update_delta] to find out how much time to simulate.
+    ///
+    /// While no frames are drawn, for example while the window is minimized or
+    /// [redrawing on demand][crate::HeartBuilder::with_redraw_on_demand] without input,
+    /// updates run at the tick rate instead.
+    Variable {
+        /// The maximum amount of time simulated by one update.
+        max_delta: Duration,
+    },
+}
+
+/// Get the fixed amount of time between two [update][crate::HeartBuilder::with_update] calls.
+pub fn get_tick_duration() -> Duration {
+    state::get().tick_duration
+}
+
+/// Get the number of [update][crate::HeartBuilder::with_update] calls that have finished so far.
+pub fn get_ticks() -> u64 {
+    state::get().ticks
+}
+
+/// Get the amount of time that the current [update][crate::HeartBuilder::with_update] call simulates.
+///
+/// This is the same as [get_tick_duration], except with the [Variable][TimestepPolicy::Variable] policy.
+pub fn get_update_delta() -> Duration {
+    state::get().update_delta
+}
+
+/// Get the real time that has passed since the [load][crate::HeartBuilder::with_load] functions were called.
+pub fn get_elapsed() -> Duration {
+    state::get()
+        .start
+        .map_or(Duration::ZERO, |start| start.elapsed())
+}
+
+/// Get the real time that has passed between the previous frame and the current one.
+///
+/// This should be used for animations in [draw][crate::HeartBuilder::with_draw] functions.
+pub fn get_delta() -> Duration {
+    state::get().delta
+}
+
+/// Get how far the current frame is between the previous and the next
+/// [update][crate::HeartBuilder::with_update] call, from 0 inclusive to 1 exclusive.
+///
+/// [Draw][crate::HeartBuilder::with_draw] functions can use this to interpolate between the
+/// previous and the current state of the game, which makes movement smooth even if the
+/// refresh rate is not a multiple of the tick rate.
+pub fn get_alpha() -> f32 {
+    state::get().alpha
+}
+
+/// Get the number of frames drawn per second, smoothed over the last few frames.
+pub fn get_fps() -> f32 {
+    state::get().fps.get()
+}
+
+/// Get the number of [update][crate::HeartBuilder::with_update] calls per second,
+/// smoothed over the last few ticks.
+pub fn get_ups() -> f32 {
+    state::get().ups.get()
+}
@@ -0,0 +1,94 @@
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+const SMOOTHING: f32 = 0.1;
+
+pub(crate) struct Counter {
+    last: Option<Instant>,
+    interval: f32,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Self {
+            last: None,
+            interval: 0.0,
+        }
+    }
+
+    fn count(&mut self, now: Instant) -> Duration {
+        let delta = self.last.map_or(Duration::ZERO, |last| now - last);
+        if self.last.is_some() {
+            self.interval = match self.interval {
+                0.0 => delta.as_secs_f32(),
+                interval => interval + (delta.as_secs_f32() - interval) * SMOOTHING,
+            };
+        }
+        self.last = Some(now);
+        delta
+    }
+
+    pub(crate) fn get(&self) -> f32 {
+        match self.interval {
+            0.0 => 0.0,
+            interval => 1.0 / interval,
+        }
+    }
+}
+
+pub(crate) struct State {
+    pub(crate) tick_duration: Duration,
+    pub(crate) ticks: u64,
+    pub(crate) update_delta: Duration,
+    pub(crate) start: Option<Instant>,
+    pub(crate) delta: Duration,
+    pub(crate) alpha: f32,
+    pub(crate) fps: Counter,
+    pub(crate) ups: Counter,
+}
+
+impl State {
+    const fn new(tick_duration: Duration, start: Option<Instant>) -> Self {
+        Self {
+            tick_duration,
+            ticks: 0,
+            update_delta: tick_duration,
+            start,
+            delta: Duration::ZERO,
+            alpha: 0.0,
+            fps: Counter::new(),
+            ups: Counter::new(),
+        }
+    }
+}
+
+static STATE: Mutex<State> = Mutex::new(State::new(Duration::ZERO, None));
+
+pub(crate) fn init(tick_duration: Duration) {
+    *get() = State::new(tick_duration, Some(Instant::now()));
+}
+
+pub(crate) fn get() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap()
+}
+
+pub(crate) fn set_update_delta(update_delta: Duration) {
+    get().update_delta = update_delta;
+}
+
+pub(crate) fn tick() {
+    let state = &mut *get();
+    state.ticks += 1;
+    state.ups.count(Instant::now());
+}
+
+pub(crate) fn frame() {
+    let state = &mut *get();
+    state.delta = state.fps.count(Instant::now());
+}
+
+pub(crate) fn set_alpha(alpha: f32) {
+    get().alpha = alpha;
+}
@@ -0,0 +1,234 @@
+//! Control over the windows.
+//!
+//! Contains functions for changing and querying windows while
+//! [heart][crate] is running. These functions should only be called
+//! from callbacks, after the windows have been created.
+//!
+//! Every window has a name. The window created by [heart][crate] is called [MAIN],
+//! and more windows can be added with [with_window][crate::HeartBuilder::with_window].
+//! Functions in this module act on the [current] window.
+//!
+//! Sizes and positions are in logical pixels, which take the [scale factor][get_scale_factor]
+//! of the monitor into account, unless [with_physical_pixels][crate::HeartBuilder::with_physical_pixels] is used.
+//!
+//! In [headless][crate::HeartBuilder::headless] mode there is no window,
+//! so functions that change the window do nothing.
+//!
+//! See also:  
+//! [with size][crate::HeartBuilder::with_size]  
+//! [with fullscreen][crate::HeartBuilder::with_fullscreen]  
+//! [with window][crate::HeartBuilder::with_window]  
+
+use crate::{Draw, app, executor, graphics};
+
+pub(crate) mod state;
+
+/// The name of the main window.
+pub const MAIN: &str = "main";
+
+/// Builder for an additional window.
+///
+/// Provides `with_*` methods to configure the window before adding it
+/// with [with_window][crate::HeartBuilder::with_window].
+pub struct Builder {
+    pub(crate) config: app::WindowConfig,
+    pub(crate) draw: Vec<executor::Callback>,
+}
+
+impl Builder {
+    /// Creates a builder for a window with the given name.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            config: app::WindowConfig::new(name),
+            draw: Vec::new(),
+        }
+    }
+
+    /// Sets the title of the window.
+    pub fn with_title<S>(mut self, title: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.config.title = Some(title.into());
+        self
+    }
+
+    /// Sets the initial size of the window in pixels.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.config.size = Some((width, height));
+        self
+    }
+
+    /// Sets the minimum size of the window in pixels.
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.config.min_size = Some((width, height));
+        self
+    }
+
+    /// Sets the maximum size of the window in pixels.
+    pub fn with_max_size(mut self, width: u32, height: u32) -> Self {
+        self.config.max_size = Some((width, height));
+        self
+    }
+
+    /// Sets the initial position of the window on the desktop in pixels.
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.config.position = Some((x, y));
+        self
+    }
+
+    /// Sets whether the window can be resized by the user. Default is `true`.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.config.resizable = resizable;
+        self
+    }
+
+    /// Sets whether the window has a title bar and borders. Default is `true`.
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.config.decorations = decorations;
+        self
+    }
+
+    /// Makes the window start in fullscreen.
+    pub fn with_fullscreen(mut self, fullscreen: Fullscreen) -> Self {
+        self.config.fullscreen = Some(fullscreen);
+        self
+    }
+
+    /// Sets the way frames are presented. Default is [Vsync][graphics::PresentMode::Vsync].
+    pub fn with_present_mode(mut self, present_mode: graphics::PresentMode) -> Self {
+        self.config.present_mode = present_mode;
+        self
+    }
+
+    /// Adds a function to be called once every frame to draw this window.
+    ///
+    /// See [Draw] for accepted functions.
+    pub fn with_draw<F, A>(mut self, mut draw: F) -> Self
+    where
+        F: Draw<A> + 'static,
+    {
+        self.draw.push(Box::new(move |state| draw.call(state)));
+        self
+    }
+}
+
+/// Represents a fullscreen mode for the window.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Fullscreen {
+    /// Covers the whole monitor with a borderless window.
+    Borderless,
+
+    /// Takes exclusive control of the monitor.
+    ///
+    /// Uses the video mode that matches the size of the window,
+    /// or the largest available one if there is no match.
+    Exclusive,
+}
+
+/// Get the name of the current window.
+///
+/// This is the window that is being drawn in [draw][crate::HeartBuilder::with_draw] functions,
+/// and the window that the event came from in input functions. Otherwise it is [MAIN].
+pub fn current() -> &'static str {
+    state::current()
+}
+
+/// Calls `f` with the window called `name` as the [current] window.
+///
+/// This allows changing other windows than the current one.
+pub fn with_current<F, R>(name: &'static str, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    state::with_current(name, f)
+}
+
+/// Sets the title of the window.
+pub fn set_title(title: &str) {
+    if let Some(window) = state::get() {
+        window.set_title(title);
+    }
+}
+
+/// Requests a new size for the window in pixels.
+///
+/// The window may not be resized immediately, or at all.
+pub fn set_size(width: u32, height: u32) {
+    if let Some(window) = state::get() {
+        let _ = window.request_inner_size(state::size(width, height));
+    }
+}
+
+/// Get the width and height of the window's drawable area in pixels.
+pub fn get_size() -> (u32, u32) {
+    state::get_size()
+}
+
+/// Get the scale factor of the monitor the window is on.
+///
+/// Unless [with_physical_pixels][crate::HeartBuilder::with_physical_pixels] is used,
+/// pixels in [heart][crate] are logical pixels, which are this many physical pixels wide.
+pub fn get_scale_factor() -> f64 {
+    state::get().map_or(1.0, |window| window.scale_factor())
+}
+
+/// Enters the given [Fullscreen] mode, or leaves fullscreen if `None`.
+pub fn set_fullscreen(fullscreen: Option<Fullscreen>) {
+    let Some(window) = state::get() else {
+        return;
+    };
+    let size = window.inner_size();
+    window.set_fullscreen(fullscreen.and_then(|fullscreen| {
+        app::create_fullscreen(
+            fullscreen,
+            window.current_monitor(),
+            Some((size.width, size.height)),
+        )
+    }));
+}
+
+/// Check if the window is in fullscreen.
+pub fn is_fullscreen() -> bool {
+    state::get().is_some_and(|window| window.fullscreen().is_some())
+}
+
+/// Minimizes or restores the window.
+pub fn set_minimized(minimized: bool) {
+    if let Some(window) = state::get() {
+        window.set_minimized(minimized);
+    }
+}
+
+/// Maximizes or restores the window.
+pub fn set_maximized(maximized: bool) {
+    if let Some(window) = state::get() {
+        window.set_maximized(maximized);
+    }
+}
+
+/// Check if the window is maximized.
+pub fn is_maximized() -> bool {
+    state::get().is_some_and(|window| window.is_maximized())
+}
+
+/// Shows or hides the window.
+pub fn set_visible(visible: bool) {
+    if let Some(window) = state::get() {
+        window.set_visible(visible);
+    }
+}
+
+/// Check if the window is visible.
+///
+/// Returns `true` if the visibility cannot be determined.
+pub fn is_visible() -> bool {
+    state::get().is_none_or(|window| window.is_visible().unwrap_or(true))
+}
+
+/// Requests the window to close.
+///
+/// Closing the [MAIN] window ends [heart][crate]. The window is closed once the current callback returns.
+pub fn close() {
+    state::request_close();
+}
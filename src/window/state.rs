@@ -0,0 +1,109 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use super::MAIN;
+
+static WINDOWS: Mutex<Vec<(&'static str, Arc<winit::window::Window>)>> = Mutex::new(Vec::new());
+
+static HEADLESS_SIZE: Mutex<Option<(u32, u32)>> = Mutex::new(None);
+
+static CURRENT: Mutex<&'static str> = Mutex::new(MAIN);
+
+static CLOSE_REQUESTS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+static PHYSICAL_PIXELS: Mutex<bool> = Mutex::new(false);
+
+pub(crate) fn insert(name: &'static str, window: Arc<winit::window::Window>) {
+    WINDOWS.lock().unwrap().push((name, window));
+}
+
+pub(crate) fn remove(name: &'static str) {
+    WINDOWS.lock().unwrap().retain(|(other, _)| *other != name);
+}
+
+pub(crate) fn init_headless(width: u32, height: u32) {
+    *HEADLESS_SIZE.lock().unwrap() = Some((width, height));
+}
+
+pub(crate) fn clear() {
+    WINDOWS.lock().unwrap().clear();
+    *HEADLESS_SIZE.lock().unwrap() = None;
+    *CURRENT.lock().unwrap() = MAIN;
+    CLOSE_REQUESTS.lock().unwrap().clear();
+    *PHYSICAL_PIXELS.lock().unwrap() = false;
+}
+
+pub(crate) fn set_physical_pixels(physical_pixels: bool) {
+    *PHYSICAL_PIXELS.lock().unwrap() = physical_pixels;
+}
+
+pub(crate) fn scale_factor(window: &winit::window::Window) -> f64 {
+    if *PHYSICAL_PIXELS.lock().unwrap() {
+        1.0
+    } else {
+        window.scale_factor()
+    }
+}
+
+pub(crate) fn size(width: u32, height: u32) -> winit::dpi::Size {
+    if *PHYSICAL_PIXELS.lock().unwrap() {
+        winit::dpi::PhysicalSize::new(width, height).into()
+    } else {
+        winit::dpi::LogicalSize::new(width, height).into()
+    }
+}
+
+pub(crate) fn position(x: i32, y: i32) -> winit::dpi::Position {
+    if *PHYSICAL_PIXELS.lock().unwrap() {
+        winit::dpi::PhysicalPosition::new(x, y).into()
+    } else {
+        winit::dpi::LogicalPosition::new(x, y).into()
+    }
+}
+
+pub(crate) fn get() -> Option<Arc<winit::window::Window>> {
+    let current = current();
+    WINDOWS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(name, _)| *name == current)
+        .map(|(_, window)| window.clone())
+}
+
+pub(crate) fn get_size() -> (u32, u32) {
+    match get() {
+        Some(window) => {
+            let size = window.inner_size().to_logical(scale_factor(&window));
+            (size.width, size.height)
+        }
+        None => HEADLESS_SIZE.lock().unwrap().unwrap_or((0, 0)),
+    }
+}
+
+pub(crate) fn current() -> &'static str {
+    *CURRENT.lock().unwrap()
+}
+
+struct Restore(&'static str);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        *CURRENT.lock().unwrap_or_else(PoisonError::into_inner) = self.0;
+    }
+}
+
+pub(crate) fn with_current<F, R>(name: &'static str, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _restore = Restore(std::mem::replace(&mut *CURRENT.lock().unwrap(), name));
+    f()
+}
+
+pub(crate) fn request_close() {
+    CLOSE_REQUESTS.lock().unwrap().push(current());
+}
+
+pub(crate) fn take_close_requests() -> Vec<&'static str> {
+    std::mem::take(&mut *CLOSE_REQUESTS.lock().unwrap())
+}
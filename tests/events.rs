@@ -0,0 +1,72 @@
+use heart::{events, testing::Harness, time};
+
+struct PlayerDied(u32);
+
+struct GameOver;
+
+#[derive(Default)]
+struct Log(Vec<(u64, &'static str, u32)>);
+
+fn harness() -> Harness {
+    Harness::new(
+        heart::build()
+            .headless(16, 16)
+            .with_load(Log::default)
+            .with_event(|log: &mut Log, event: &PlayerDied| {
+                log.0.push((time::get_ticks(), "player died", event.0));
+                if event.0 == 1 {
+                    events::send(GameOver);
+                }
+            })
+            .with_event(|log: &mut Log, _: &GameOver| {
+                log.0.push((time::get_ticks(), "game over", 0));
+            })
+            .with_update(|| {
+                if time::get_ticks() == 0 {
+                    events::send(PlayerDied(1));
+                    events::send(PlayerDied(2));
+                }
+            }),
+    )
+}
+
+#[test]
+fn order() {
+    let mut harness = harness();
+    harness.step(2);
+    assert_eq!(
+        harness.state::<Log>().unwrap().0,
+        [(1, "player died", 1), (1, "player died", 2)]
+    );
+}
+
+#[test]
+fn sent_during_delivery() {
+    let mut harness = harness();
+    harness.step(2);
+    assert_eq!(harness.state::<Log>().unwrap().0.len(), 2);
+
+    harness.step(1);
+    assert_eq!(harness.state::<Log>().unwrap().0[2], (2, "game over", 0));
+
+    harness.step(5);
+    assert_eq!(harness.state::<Log>().unwrap().0.len(), 3);
+}
+
+#[test]
+fn sent_from_thread() {
+    let mut harness = Harness::new(
+        heart::build()
+            .headless(16, 16)
+            .with_load(Log::default)
+            .with_event(|log: &mut Log, event: &PlayerDied| {
+                log.0.push((time::get_ticks(), "player died", event.0));
+            }),
+    );
+
+    std::thread::spawn(|| events::send(PlayerDied(3)))
+        .join()
+        .unwrap();
+    harness.step(1);
+    assert_eq!(harness.state::<Log>().unwrap().0, [(0, "player died", 3)]);
+}
@@ -0,0 +1,61 @@
+use heart::{
+    keyboard::Scancode,
+    schedule::{self, Handle, Stage},
+    testing::Harness,
+};
+
+struct Cutscene {
+    ticks: u32,
+    skips: u32,
+}
+
+struct Counter {
+    presses: u32,
+    handle: Handle,
+}
+
+#[test]
+fn skip_cutscene() {
+    let mut harness = Harness::new(heart::build().headless(16, 16).with_load(|| {
+        let playing = schedule::add(Stage::Update, |cutscene: &mut Cutscene| {
+            cutscene.ticks += 1;
+        });
+        schedule::add_key_pressed(move |cutscene: &mut Cutscene, scancode| {
+            if scancode == Scancode::Escape {
+                cutscene.skips += 1;
+                schedule::remove(playing);
+            }
+        });
+        Cutscene { ticks: 0, skips: 0 }
+    }));
+
+    harness.step(2);
+    harness.key_pressed(Scancode::Escape);
+    harness.step(3);
+    let cutscene = harness.state::<Cutscene>().unwrap();
+    assert_eq!((cutscene.ticks, cutscene.skips), (2, 1));
+}
+
+#[test]
+fn remove_key_pressed() {
+    let mut harness = Harness::new(
+        heart::build()
+            .headless(16, 16)
+            .with_load(|| Counter {
+                presses: 0,
+                handle: schedule::add_key_pressed(|counter: &mut Counter, _| {
+                    counter.presses += 1;
+                }),
+            })
+            .with_update(|counter: &mut Counter| {
+                if counter.presses == 1 {
+                    schedule::remove(counter.handle);
+                }
+            }),
+    );
+
+    harness.key_pressed(Scancode::Space);
+    harness.step(1);
+    harness.key_pressed(Scancode::Space);
+    assert_eq!(harness.state::<Counter>().unwrap().presses, 1);
+}
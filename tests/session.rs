@@ -0,0 +1,14 @@
+use std::panic;
+
+use heart::{graphics, testing::Harness};
+
+#[test]
+fn graphics_outside_session() {
+    let result = panic::catch_unwind(|| graphics::rectangle(0.0, 0.0, 1.0, 1.0));
+    assert!(result.is_err());
+
+    let mut harness = Harness::new(heart::build().headless(16, 16).with_draw(|| {
+        graphics::rectangle(0.0, 0.0, 1.0, 1.0);
+    }));
+    harness.draw().unwrap();
+}
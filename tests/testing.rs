@@ -0,0 +1,89 @@
+use heart::{
+    EventResult,
+    mouse::{self, Button},
+    testing::Harness,
+};
+
+#[derive(Default)]
+struct Clicks {
+    moves: Vec<(f32, f32, f32, f32)>,
+    presses: Vec<(f32, f32)>,
+}
+
+fn harness() -> Harness {
+    Harness::new(
+        heart::build()
+            .headless(16, 16)
+            .with_load(Clicks::default)
+            .with_mouse_moved(|clicks: &mut Clicks, x, y, dx, dy| {
+                clicks.moves.push((x, y, dx, dy));
+            })
+            .with_mouse_pressed(|clicks: &mut Clicks, x, y, button| {
+                clicks.presses.push((x, y));
+                if button == Button::Right {
+                    EventResult::Handled
+                } else {
+                    EventResult::Ignored
+                }
+            }),
+    )
+}
+
+#[test]
+fn mouse() {
+    let mut harness = harness();
+
+    harness.mouse_moved(4.0, 5.0);
+    harness.mouse_moved(6.0, 3.0);
+    harness.mouse_pressed(Button::Left);
+    assert!(mouse::is_pressed(Button::Left));
+    assert!(!mouse::is_captured());
+
+    let clicks = harness.state::<Clicks>().unwrap();
+    assert_eq!(clicks.moves, [(4.0, 5.0, 0.0, 0.0), (6.0, 3.0, 2.0, -2.0)]);
+    assert_eq!(clicks.presses, [(6.0, 3.0)]);
+
+    harness.mouse_released(Button::Left);
+    harness.mouse_pressed(Button::Right);
+    assert!(!mouse::is_pressed(Button::Left));
+    assert!(mouse::is_captured());
+
+    harness.mouse_released(Button::Right);
+    assert!(!mouse::is_captured());
+}
+
+#[test]
+fn missing_state() {
+    let mut harness = harness();
+    assert!(harness.state::<Clicks>().is_some());
+    assert!(harness.state::<u32>().is_none());
+}
+
+#[test]
+fn ticks() {
+    let mut harness = harness();
+    harness.step(3);
+    harness.draw().unwrap();
+    assert_eq!(heart::time::get_ticks(), 3);
+}
+
+#[test]
+fn no_adapter() {
+    let mut harness = Harness::new(
+        heart::build()
+            .headless(16, 16)
+            .with_backends(heart::wgpu::Backends::empty())
+            .with_load(|| 0u32)
+            .with_update(|ticks: &mut u32| *ticks += 1),
+    );
+    harness.step(2);
+    assert_eq!(harness.state::<u32>(), Some(&mut 2));
+    assert!(matches!(harness.draw(), Err(heart::Error::NoAdapter)));
+}
+
+#[test]
+#[should_panic(expected = "already running on this thread")]
+fn second_harness_on_same_thread() {
+    let _first = harness();
+    let _second = harness();
+}